@@ -88,9 +88,8 @@ fn read_folder_result(
     list: &mut Vec<String>,
 ) -> anyhow::Result<()> {
     for entry in result.entries {
-        match entry {
-            files::Metadata::Folder(folder_meta_data) => list.push(folder_meta_data.name),
-            _ => (),
+        if let files::Metadata::Folder(folder_meta_data) = entry {
+            list.push(folder_meta_data.name)
         }
     }
     if result.has_more {
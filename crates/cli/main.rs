@@ -2,6 +2,7 @@ mod dropbox;
 mod services;
 
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 struct Cli {
@@ -17,6 +18,25 @@ enum Commands {
     Verify,
     Run,
     AtcoderList,
+    /// 入力と想定解の出力をリポジトリのテストケースとして保存する
+    Promote {
+        /// `aizu_online_judge`のようなサービス名
+        #[arg(value_parser = services::SERVICE_NAMES)]
+        service: String,
+        problem_id: String,
+        /// 入力のファイル
+        input: PathBuf,
+        /// 想定解の出力のファイル
+        #[arg(
+            long,
+            conflicts_with = "reference",
+            required_unless_present = "reference"
+        )]
+        output: Option<PathBuf>,
+        /// 入力を標準入力に与えて想定解の出力を得るコマンド
+        #[arg(long)]
+        reference: Option<String>,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
@@ -29,6 +49,19 @@ fn main() -> anyhow::Result<()> {
         Some(Commands::Verify) => services::verify()?,
         Some(Commands::Run) => run()?,
         Some(Commands::AtcoderList) => dropbox::list()?,
+        Some(Commands::Promote {
+            service,
+            problem_id,
+            input,
+            output,
+            reference,
+        }) => services::promote(
+            service,
+            problem_id,
+            input,
+            output.as_deref(),
+            reference.as_deref(),
+        )?,
         None => (),
     }
     Ok(())
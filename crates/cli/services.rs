@@ -3,17 +3,20 @@ mod atcoder;
 mod library_checker;
 mod yukicoder;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use std::{
     ffi::OsStr,
-    fs::File,
+    fs::{self, File},
     io::{Read, Write},
     path::Path,
-    process::Command,
+    process::{Command, Stdio},
+    thread,
 };
 use verify_core::{
+    local_cases,
     service::{
-        aizu_online_judge::AizuOnlineJudge, library_checker::LibraryChecker, yukicoder::Yukicoder,
+        aizu_online_judge::AizuOnlineJudge, atcoder::AtCoder, library_checker::LibraryChecker,
+        yukicoder::Yukicoder,
     },
     ProblemForVerify, Service,
 };
@@ -21,13 +24,11 @@ use verify_core::{
 pub fn save_metadata() -> anyhow::Result<()> {
     output_metadata()?;
     let root_dir = verify_core::app_cache_directory();
-    for result in root_dir.read_dir()? {
-        if let Ok(entry) = result {
-            let path = entry.path();
-            if path.extension() == Some(OsStr::new("info")) {
-                if let Err(e) = normalize(&path) {
-                    log::warn!("failed to normalize {}: {:?}", path.display(), e)
-                }
+    for entry in root_dir.read_dir()?.flatten() {
+        let path = entry.path();
+        if path.extension() == Some(OsStr::new("info")) {
+            if let Err(e) = normalize(&path) {
+                log::warn!("failed to normalize {}: {:?}", path.display(), e)
             }
         }
     }
@@ -36,13 +37,13 @@ pub fn save_metadata() -> anyhow::Result<()> {
 
 fn normalize(path: &Path) -> anyhow::Result<()> {
     let mut buf = String::new();
-    let mut file = File::open(&path)?;
+    let mut file = File::open(path)?;
     file.read_to_string(&mut buf)?;
     let mut v = buf.split_ascii_whitespace().collect::<Vec<_>>();
     v.sort();
     v.dedup();
-    let mut file = File::create(&path)?;
-    file.write_all(&v.into_iter().collect::<String>().as_bytes())?;
+    let mut file = File::create(path)?;
+    file.write_all(v.into_iter().collect::<String>().as_bytes())?;
     Ok(())
 }
 
@@ -65,7 +66,7 @@ fn output_metadata() -> anyhow::Result<()> {
 }
 
 pub fn fetch_testcases() -> anyhow::Result<()> {
-    if let Ok(mut file) = File::open(&LibraryChecker::info_path()) {
+    if let Ok(mut file) = File::open(LibraryChecker::info_path()) {
         let mut s = String::new();
         file.read_to_string(&mut s)?;
         library_checker::fetch_problem_repository()?;
@@ -75,7 +76,7 @@ pub fn fetch_testcases() -> anyhow::Result<()> {
             })?;
         }
     }
-    if let Ok(mut file) = File::open(&AizuOnlineJudge::info_path()) {
+    if let Ok(mut file) = File::open(AizuOnlineJudge::info_path()) {
         let mut s = String::new();
         file.read_to_string(&mut s)?;
         for line in s.lines() {
@@ -84,7 +85,7 @@ pub fn fetch_testcases() -> anyhow::Result<()> {
             })?;
         }
     }
-    if let Ok(mut file) = File::open(&Yukicoder::info_path()) {
+    if let Ok(mut file) = File::open(Yukicoder::info_path()) {
         let mut s = String::new();
         file.read_to_string(&mut s)?;
         for line in s.lines() {
@@ -100,6 +101,60 @@ pub fn verify() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// テストケースを保存できるサービス名
+pub const SERVICE_NAMES: [&str; 4] = [
+    AizuOnlineJudge::SERVICE_NAME,
+    AtCoder::SERVICE_NAME,
+    LibraryChecker::SERVICE_NAME,
+    Yukicoder::SERVICE_NAME,
+];
+
+/// 入力と、ファイルか想定解のコマンドから得た出力をリポジトリのテストケースとして保存する
+/// サービス名は`SERVICE_NAMES`のいずれかであることをCLIの引数の解析で確かめる
+pub fn promote(
+    service: &str,
+    problem_id: &str,
+    input: &Path,
+    output: Option<&Path>,
+    reference: Option<&str>,
+) -> anyhow::Result<()> {
+    let input = fs::read(input).with_context(|| format!("could not read {}", input.display()))?;
+    let expect = match (output, reference) {
+        (Some(output), _) => {
+            fs::read(output).with_context(|| format!("could not read {}", output.display()))?
+        }
+        (None, Some(reference)) => run_reference(reference, &input)?,
+        (None, None) => return Err(anyhow!("either output or reference is required")),
+    };
+    let dir = local_cases::problem_dir(service, problem_id)?;
+    local_cases::save(&dir, &input, &expect)?;
+    Ok(())
+}
+
+/// シェルでコマンドを実行し、入力を標準入力に与えて標準出力を返す
+fn run_reference(reference: &str, input: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut child = Command::new("sh")
+        .args(["-c", reference])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not start {reference}"))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("could not open stdin of reference")?;
+    let input = input.to_vec();
+    // 出力を読みながら書き込まないと、出力の多いコマンドが止まることがある
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    writer.join().ok();
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(anyhow!("{reference} finished with {}", output.status))
+    }
+}
+
 fn blocking_client() -> reqwest::Result<reqwest::blocking::Client> {
     reqwest::blocking::Client::builder().build()
 }
@@ -30,12 +30,12 @@ pub fn fetch_testcases(problem: &ProblemForVerify) -> anyhow::Result<()> {
         .timeout(Duration::from_secs(5))
         .send()?
         .json()?;
-    File::create(AizuOnlineJudge::header_path(&problem_id)?)?
+    File::create(AizuOnlineJudge::header_path(problem_id)?)?
         .write_all(serde_json::to_string(&headers)?.as_bytes())?;
 
     for header in headers.headers {
         let serial = header.serial;
-        let in_path = header.in_path(&problem_id)?;
+        let in_path = header.in_path(problem_id)?;
         if !in_path.exists() {
             let in_url =
                 format!("https://judgedat.u-aizu.ac.jp/testcases/{problem_id}/{serial}/in");
@@ -46,7 +46,7 @@ pub fn fetch_testcases(problem: &ProblemForVerify) -> anyhow::Result<()> {
                 .bytes()?;
             File::create(in_path)?.write_all(&bytes)?;
         }
-        let out_path = header.out_path(&problem_id)?;
+        let out_path = header.out_path(problem_id)?;
         if !out_path.exists() {
             let out_url =
                 format!("https://judgedat.u-aizu.ac.jp/testcases/{problem_id}/{serial}/out");
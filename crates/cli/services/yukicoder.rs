@@ -67,7 +67,7 @@ impl YukicoderTask {
             } => {
                 let client = super::blocking_client()?;
                 let path = header_path(&problem_dir);
-                let testcases = YukicoderHeader::from_file(&path);
+                let testcases = YukicoderHeader::from_file(&path)?;
                 download(&testcases, &problem_id, client, &problem_dir)?;
                 Ok(Self::Done)
             }
@@ -1,6 +1,8 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 use std::io::{BufRead, BufReader, Read, Write};
-use verify::{AizuOnlineJudge, LibraryChecker, Solver, Yukicoder};
+use verify::{
+    ensure, AizuOnlineJudge, CheckResult, Checker, LibraryChecker, Solver, TokenReader, Yukicoder,
+};
 
 #[test]
 fn test() {
@@ -41,6 +43,60 @@ impl Solver for Itp1_1aTLE {
     }
 }
 
+#[derive(AizuOnlineJudge)]
+pub struct Itp1_1aExit;
+impl Solver for Itp1_1aExit {
+    const PROBLEM_ID: &'static str = "ITP1_1_A";
+    fn solve(_read: impl Read, mut _write: impl Write) {
+        std::process::exit(1)
+    }
+}
+
+/// 大文字小文字を区別せずに想定解と比較する
+pub struct IgnoreCaseChecker;
+impl Checker for IgnoreCaseChecker {
+    fn check(
+        &self,
+        _input: &mut TokenReader,
+        expect: &mut TokenReader,
+        actual: &mut TokenReader,
+    ) -> CheckResult {
+        while !expect.is_eof() {
+            let (e, a) = (expect.token()?, actual.token()?);
+            ensure(e.eq_ignore_ascii_case(a), format!("expect {e}, actual {a}"))?;
+        }
+        actual.expect_eof()
+    }
+}
+
+#[derive(AizuOnlineJudge)]
+pub struct Itp1_1aChecker;
+impl Solver for Itp1_1aChecker {
+    const PROBLEM_ID: &'static str = "ITP1_1_A";
+    const CHECKER: Option<&'static dyn Checker> = Some(&IgnoreCaseChecker);
+    fn solve(_read: impl Read, mut write: impl Write) {
+        writeln!(write, "HELLO WORLD").ok();
+    }
+}
+
+/// # A + B
+/// <https://judge.yosupo.jp/problem/aplusb>
+///
+/// ```input
+/// 1 2
+/// ```
+///
+/// ```output
+/// 3
+/// ```
+///
+/// ```input
+/// 1000000000 1000000000
+/// ```
+///
+/// ```output
+/// 2000000000
+/// ```
 #[derive(LibraryChecker)]
 pub struct APlusB;
 impl Solver for APlusB {
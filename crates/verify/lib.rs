@@ -1,7 +1,11 @@
 pub use verify_attr::{AizuOnlineJudge, AtCoder, LibraryChecker, Yukicoder};
 pub use verify_core::attribute::VerifyAttribute;
+pub use verify_core::checker::{ensure, CheckResult, Checker, TokenReader, Verdict};
+pub use verify_core::interactor::{read_from_solver, Interactor};
+pub use verify_core::judge::CompareMode;
 pub use verify_core::service::{
     aizu_online_judge::AizuOnlineJudge, atcoder::AtCoder, library_checker::LibraryChecker,
     yukicoder::Yukicoder,
 };
+pub use verify_core::stress::{stress, Budget, Counterexample, Generator, StressReport};
 pub use verify_core::{Service, Solver, Verifiable};
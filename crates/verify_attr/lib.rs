@@ -27,14 +27,38 @@ fn derive(input: TokenStream, service: Ident) -> TokenStream {
     // verifyはファイルを読み込んで行う
     let save_metadata = save_metadata(&input.ident);
     let verify = verify(&input.ident);
+    let samples = samples(&input);
     quote! {
         #save_metadata
         #implement
         #verify
+        #samples
     }
     .into()
 }
 
+/// ドキュメントコメントの入出力例ごとに、`Solver::assert`で確かめるテストを作る
+fn samples(input: &DeriveInput) -> proc_macro2::TokenStream {
+    let ident = &input.ident;
+    let doc = verify_core::doc_sample::doc_comment(&input.attrs);
+    let samples = match verify_core::doc_sample::parse(&doc) {
+        Ok(samples) => samples,
+        Err(message) => return syn::Error::new_spanned(ident, message).to_compile_error(),
+    };
+    let tests = samples.iter().enumerate().map(|(i, sample)| {
+        let fn_name = Ident::new(&format!("sample_{ident}_{}", i + 1), Span::call_site());
+        let (input, output) = (&sample.input, &sample.output);
+        quote! {
+            #[test]
+            #[cfg_attr(coverage_nightly, coverage(off))]
+            fn #fn_name() {
+                <#ident as ::verify::Solver>::assert(#input, #output)
+            }
+        }
+    });
+    quote! { #(#tests)* }
+}
+
 fn save_metadata(ident: &Ident) -> proc_macro2::TokenStream {
     let fn_name: Ident = Ident::new(&format!("save_metadata_{ident}"), Span::call_site());
     quote! {
@@ -49,11 +73,13 @@ fn save_metadata(ident: &Ident) -> proc_macro2::TokenStream {
 
 fn implement(ident: &Ident, service: &Ident) -> proc_macro2::TokenStream {
     let md_name = LitStr::new(&format!("result_{ident}.md"), Span::call_site());
+    let entry_point = LitStr::new(&format!("::verify_{ident}"), Span::call_site());
     quote! {
         #[cfg_attr(feature = "verify_result", doc = include_str!(#md_name))]
         #[cfg_attr(coverage_nightly, coverage(off))]
         impl ::verify::Verifiable for #ident {
             type SERVICE = ::verify::#service;
+            const ENTRY_POINT: Option<&'static str> = Some(concat!(module_path!(), #entry_point));
         }
     }
 }
@@ -0,0 +1,77 @@
+use crate::{config::VerifyConfig, judge::JudgeResult};
+use std::{
+    env,
+    fs::{create_dir_all, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// 1回の実行で共有する成果物のディレクトリ 実行ごとに開始時刻で分ける
+fn run_dir() -> &'static Path {
+    static RUN_DIR: OnceLock<PathBuf> = OnceLock::new();
+    RUN_DIR.get_or_init(|| {
+        let base = match &VerifyConfig::get().artifacts_dir {
+            // Markdownからリンクするため絶対パスにする
+            Some(dir) => env::current_dir().unwrap_or_default().join(dir),
+            None => crate::app_cache_directory().join("artifacts"),
+        };
+        base.join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string())
+    })
+}
+
+/// 問題ごとの成果物のディレクトリ 保存するまで作成はしない
+pub fn problem_dir(service_name: &str, problem_id: &str) -> PathBuf {
+    run_dir().join(service_name).join(problem_id)
+}
+
+/// 解答の出力と判定のメッセージを保存し、保存したファイルを`result`に記録する
+/// 保存に失敗しても判定は続ける
+pub(crate) fn save(result: &mut JudgeResult, output: Option<&[u8]>, dir: &Path) {
+    let name = result.name.replace(['/', '\\'], "_");
+    let mut log = format!(
+        "status: {}\nmessage: {}\ndiagnostic: {}\n",
+        result.status,
+        result.message.as_deref().unwrap_or_default(),
+        result
+            .diagnostic
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    );
+    if let Some(backtrace) = result.panic.as_ref().and_then(|p| p.backtrace.as_ref()) {
+        log.push_str(&format!("backtrace:\n{backtrace}\n"));
+    }
+    let files = output
+        .map(|output| (dir.join(format!("{name}.out")), output))
+        .into_iter()
+        .chain([(dir.join(format!("{name}.log")), log.as_bytes())]);
+    result.artifacts.clear();
+    for (path, buf) in files {
+        let saved = create_dir_all(dir).and_then(|_| File::create(&path)?.write_all(buf));
+        match saved {
+            Ok(()) => result.artifacts.push(path),
+            Err(e) => println!("could not save {}: {e}", path.display()),
+        }
+    }
+}
+
+#[test]
+fn save_test() {
+    use crate::judge::JudgeStatus;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut result = JudgeResult::new("sample/01.txt".to_string(), JudgeStatus::WrongAnswer);
+    save(&mut result, Some(b"42\n"), dir.path());
+    assert_eq!(
+        result.artifacts,
+        [
+            dir.path().join("sample_01.txt.out"),
+            dir.path().join("sample_01.txt.log")
+        ]
+    );
+    assert_eq!(std::fs::read(&result.artifacts[0]).unwrap(), b"42\n");
+    assert!(std::fs::read_to_string(&result.artifacts[1])
+        .unwrap()
+        .starts_with("status: WA\n"));
+}
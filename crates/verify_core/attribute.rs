@@ -1,6 +1,8 @@
+use crate::{checker::Checker, interactor::Interactor, judge::CompareMode};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
@@ -12,6 +14,24 @@ pub struct VerifyAttribute {
     pub problem_id: String,
     pub epsilon: Option<f64>,
     pub time_limit_ms: u64,
+    pub memory_limit_mb: u64,
+    pub stack_size: usize,
+    pub output_limit_bytes: usize,
+    /// Noneの場合はサービスごとの既定の方法で判定する
+    pub compare_mode: Option<CompareMode>,
+    /// 各ケースを繰り返し実行する回数 Noneの場合は設定に従う
+    pub repeat: Option<usize>,
+    /// 指定されていれば比較方法の代わりに使う
+    #[serde(skip)]
+    pub checker: Option<&'static dyn Checker>,
+    /// 指定されていれば解答と対話させて判定する
+    #[serde(skip)]
+    pub interactor: Option<&'static dyn Interactor>,
+    /// ACでなかったケースの成果物を保存するディレクトリ
+    #[serde(skip)]
+    pub artifact_dir: Option<PathBuf>,
+    pub isolated: bool,
+    pub entry_point: Option<String>,
 }
 
 impl Parse for VerifyAttribute {
@@ -20,6 +40,12 @@ impl Parse for VerifyAttribute {
         let mut problem_id = None;
         let mut epsilon = None;
         let mut time_limit_ms = 10000;
+        let mut memory_limit_mb = 1024;
+        let mut stack_size = 100 * 1024 * 1024;
+        let mut output_limit_bytes = 256 * 1024 * 1024;
+        let mut compare_mode = None;
+        let mut repeat = None;
+        let mut isolated = true;
         for meta in punc.iter() {
             match meta {
                 Meta::NameValue(nv) => {
@@ -30,6 +56,16 @@ impl Parse for VerifyAttribute {
                         }
                         Some(ident) if ident == "eps" => epsilon = Some(parse_eps(nv)?),
                         Some(ident) if ident == "tl" => time_limit_ms = parse_tl(nv)?,
+                        Some(ident) if ident == "ml" => memory_limit_mb = parse_ml(nv)?,
+                        Some(ident) if ident == "stack_size" => stack_size = parse_stack_size(nv)?,
+                        Some(ident) if ident == "output_limit" => {
+                            output_limit_bytes = parse_output_limit(nv)?
+                        }
+                        Some(ident) if ident == "compare" => {
+                            compare_mode = Some(parse_compare(nv)?)
+                        }
+                        Some(ident) if ident == "repeat" => repeat = Some(parse_repeat(nv)?),
+                        Some(ident) if ident == "isolated" => isolated = parse_isolated(nv)?,
                         _ => {
                             return Err(Error::new(
                                 Span::call_site(),
@@ -49,6 +85,16 @@ impl Parse for VerifyAttribute {
             problem_id,
             epsilon,
             time_limit_ms,
+            memory_limit_mb,
+            stack_size,
+            output_limit_bytes,
+            compare_mode,
+            repeat,
+            checker: None,
+            interactor: None,
+            artifact_dir: None,
+            isolated,
+            entry_point: None,
         })
     }
 }
@@ -81,18 +127,117 @@ fn parse_tl(nv: &MetaNameValue) -> syn::Result<u64> {
     }
 }
 
+fn parse_ml(nv: &MetaNameValue) -> syn::Result<u64> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(litint) => litint.base10_parse(),
+            _ => Err(Error::new(Span::call_site(), "ml must be int")),
+        },
+        _ => Err(Error::new(Span::call_site(), "ml is invalid")),
+    }
+}
+fn parse_stack_size(nv: &MetaNameValue) -> syn::Result<usize> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(litint) => litint.base10_parse(),
+            _ => Err(Error::new(Span::call_site(), "stack_size must be int")),
+        },
+        _ => Err(Error::new(Span::call_site(), "stack_size is invalid")),
+    }
+}
+fn parse_output_limit(nv: &MetaNameValue) -> syn::Result<usize> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(litint) => litint.base10_parse(),
+            _ => Err(Error::new(Span::call_site(), "output_limit must be int")),
+        },
+        _ => Err(Error::new(Span::call_site(), "output_limit is invalid")),
+    }
+}
+fn parse_compare(nv: &MetaNameValue) -> syn::Result<CompareMode> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(litstr) => litstr
+                .value()
+                .parse()
+                .map_err(|e| Error::new(Span::call_site(), e)),
+            _ => Err(Error::new(Span::call_site(), "compare must be str")),
+        },
+        _ => Err(Error::new(Span::call_site(), "compare is invalid")),
+    }
+}
+fn parse_repeat(nv: &MetaNameValue) -> syn::Result<usize> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(litint) => litint.base10_parse(),
+            _ => Err(Error::new(Span::call_site(), "repeat must be int")),
+        },
+        _ => Err(Error::new(Span::call_site(), "repeat is invalid")),
+    }
+}
+fn parse_isolated(nv: &MetaNameValue) -> syn::Result<bool> {
+    match &nv.value {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Bool(litbool) => Ok(litbool.value),
+            _ => Err(Error::new(Span::call_site(), "isolated must be bool")),
+        },
+        _ => Err(Error::new(Span::call_site(), "isolated is invalid")),
+    }
+}
+
 impl ToTokens for VerifyAttribute {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let problem_id = self.problem_id.clone();
         let epsilon = self.epsilon;
         let time_limit_ms = self.time_limit_ms;
+        let memory_limit_mb = self.memory_limit_mb;
+        let stack_size = self.stack_size;
+        let output_limit_bytes = self.output_limit_bytes;
+        let compare_mode = match &self.compare_mode {
+            Some(mode) => quote!(Some(#mode)),
+            None => quote!(None),
+        };
+        let repeat = match self.repeat {
+            Some(repeat) => quote!(Some(#repeat)),
+            None => quote!(None),
+        };
+        let isolated = self.isolated;
+        let entry_point = match &self.entry_point {
+            Some(entry_point) => quote!(Some(#entry_point.to_string())),
+            None => quote!(None),
+        };
         quote!(
             ::verify::VerifyAttribute {
                 problem_id: #problem_id.to_string(),
                 epsilon: #epsilon,
-                time_limit_ms: #time_limit_ms
+                time_limit_ms: #time_limit_ms,
+                memory_limit_mb: #memory_limit_mb,
+                stack_size: #stack_size,
+                output_limit_bytes: #output_limit_bytes,
+                compare_mode: #compare_mode,
+                repeat: #repeat,
+                checker: None,
+                interactor: None,
+                artifact_dir: None,
+                isolated: #isolated,
+                entry_point: #entry_point
             }
         )
         .to_tokens(tokens)
     }
 }
+
+impl ToTokens for CompareMode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Exact => quote!(::verify::CompareMode::Exact),
+            Self::Line => quote!(::verify::CompareMode::Line),
+            Self::Token => quote!(::verify::CompareMode::Token),
+            Self::TokenIgnoreCase => quote!(::verify::CompareMode::TokenIgnoreCase),
+            Self::Float => quote!(::verify::CompareMode::Float),
+            Self::UnorderedLines => quote!(::verify::CompareMode::UnorderedLines),
+            Self::UnorderedTokens => quote!(::verify::CompareMode::UnorderedTokens),
+        }
+        .to_tokens(tokens)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{config::VerifyConfig, local_cases};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{create_dir_all, File},
+    hint::black_box,
+    path::PathBuf,
+    sync::OnceLock,
+    time::Instant,
+};
+
+/// 基準の処理を何回実行し、最も速かった回を使うか
+const CALIBRATION_RUNS: usize = 3;
+/// 基準の処理で整列する要素数
+const WORKLOAD_SIZE: usize = 1 << 18;
+
+/// 基準の処理の実行時間から求めた、この環境の速さ
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    /// この環境での基準の処理の実行時間
+    pub measured_ms: f64,
+    /// 基準とする環境での実行時間
+    pub baseline_ms: f64,
+}
+
+impl Calibration {
+    /// 実行時間制限に掛ける倍率 基準より遅い環境では1より大きい
+    pub fn speed_factor(&self) -> f64 {
+        self.measured_ms / self.baseline_ms
+    }
+}
+
+/// 保存する基準の実行時間
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    baseline_ms: f64,
+}
+
+/// 設定で有効にされていれば、初回呼び出し時に1度だけ計測した結果を返す
+pub fn get(config: &VerifyConfig) -> Option<Calibration> {
+    static CALIBRATION: OnceLock<Option<Calibration>> = OnceLock::new();
+    if !config.calibrate {
+        return None;
+    }
+    *CALIBRATION.get_or_init(|| {
+        let measured_ms = measure();
+        let baseline_ms = match config.calibration_baseline_ms {
+            Some(baseline_ms) if baseline_ms > 0.0 => baseline_ms,
+            _ if config.record_calibration_baseline => store_baseline(measured_ms)?,
+            _ => load_baseline()?,
+        };
+        let calibration = Calibration {
+            measured_ms,
+            baseline_ms,
+        };
+        println!(
+            "calibration: reference workload took {measured_ms:.1}ms against baseline {baseline_ms:.1}ms, speed factor {:.2}",
+            calibration.speed_factor()
+        );
+        Some(calibration)
+    })
+}
+
+/// 基準の処理を繰り返し実行し、最も短い実行時間(ms)を返す
+fn measure() -> f64 {
+    (0..CALIBRATION_RUNS)
+        .map(|_| {
+            let now = Instant::now();
+            black_box(reference_workload());
+            now.elapsed().as_secs_f64() * 1000.0
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// 乱数の生成、整列、畳み込みからなる決まった処理
+fn reference_workload() -> u64 {
+    let mut x: u64 = 88172645463325252;
+    let mut v = Vec::with_capacity(WORKLOAD_SIZE);
+    for _ in 0..WORKLOAD_SIZE {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        v.push(x);
+    }
+    v.sort_unstable();
+    v.iter()
+        .fold(0, |acc: u64, &y| acc.wrapping_mul(31).wrapping_add(y))
+}
+
+/// 最適化の有無で実行時間が大きく変わるため、ビルドのプロファイルごとに保存する
+/// 他の環境と共有できるよう、ワークスペースの`testcases`に置く
+fn baseline_path() -> anyhow::Result<PathBuf> {
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    Ok(local_cases::testcases_dir()?.join(format!("calibration_{profile}.json")))
+}
+
+/// ワークスペースに保存された基準の実行時間を読む なければ補正しない
+fn load_baseline() -> Option<f64> {
+    let path = match baseline_path() {
+        Ok(path) => path,
+        Err(e) => {
+            println!("could not find calibration baseline: {e}");
+            return None;
+        }
+    };
+    let Ok(file) = File::open(&path) else {
+        println!(
+            "calibration baseline {} is not found, set RUST_JUDGE_CALIBRATION_BASELINE_MS or run once with RUST_JUDGE_RECORD_CALIBRATION_BASELINE=true on the reference machine",
+            path.display()
+        );
+        return None;
+    };
+    match serde_json::from_reader::<_, Baseline>(file) {
+        Ok(baseline) if baseline.baseline_ms > 0.0 => Some(baseline.baseline_ms),
+        _ => {
+            println!("invalid calibration baseline {}", path.display());
+            None
+        }
+    }
+}
+
+/// 今回の計測結果を基準としてワークスペースに保存する
+fn store_baseline(measured_ms: f64) -> Option<f64> {
+    let baseline = Baseline {
+        baseline_ms: measured_ms,
+    };
+    let stored = baseline_path().and_then(|path| {
+        create_dir_all(path.parent().unwrap())?;
+        serde_json::to_writer(File::create(&path)?, &baseline)?;
+        Ok(path)
+    });
+    match stored {
+        Ok(path) => println!("calibration baseline is stored in {}", path.display()),
+        Err(e) => println!("could not store calibration baseline: {e}"),
+    }
+    Some(measured_ms)
+}
+
+#[test]
+fn calibration_test() {
+    assert!(measure() > 0.0);
+    let calibration = Calibration {
+        measured_ms: 150.0,
+        baseline_ms: 100.0,
+    };
+    assert_eq!(calibration.speed_factor(), 1.5);
+    assert_eq!(get(&VerifyConfig::default()), None);
+}
@@ -0,0 +1,214 @@
+use crate::judge::{diagnostic::Diagnostic, AssertResult, Assertion};
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Display, Formatter},
+    iter::Peekable,
+    str::{FromStr, SplitAsciiWhitespace},
+};
+
+/// 解答者がRustで実装するスペシャルジャッジ
+///
+/// 出力が正しければ`Ok(())`を返す
+/// 解答の出力を読む`actual`で読み込みに失敗した場合はWA、
+/// 入力や想定解の出力の読み込みに失敗した場合はチェッカーの不備としてIEになる
+pub trait Checker: Sync {
+    fn check(
+        &self,
+        input: &mut TokenReader,
+        expect: &mut TokenReader,
+        actual: &mut TokenReader,
+    ) -> CheckResult;
+}
+
+impl Debug for dyn Checker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Checker")
+    }
+}
+
+pub type CheckResult = Result<(), Verdict>;
+
+/// 出力が正しくない場合の判定
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    WrongAnswer(String),
+    /// 入力や想定解の出力が不正など、チェッカー側の失敗
+    Fail(String),
+}
+
+impl Verdict {
+    pub fn wrong_answer(message: impl Display) -> Self {
+        Self::WrongAnswer(message.to_string())
+    }
+    pub fn fail(message: impl Display) -> Self {
+        Self::Fail(message.to_string())
+    }
+}
+
+/// 条件を満たさなければWAとする
+pub fn ensure(condition: bool, message: impl Display) -> CheckResult {
+    if condition {
+        Ok(())
+    } else {
+        Err(Verdict::wrong_answer(message))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Input,
+    Expect,
+    Actual,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input => write!(f, "input"),
+            Self::Expect => write!(f, "expect"),
+            Self::Actual => write!(f, "actual"),
+        }
+    }
+}
+
+/// 空白区切りのトークンを読むリーダー
+pub struct TokenReader<'a> {
+    tokens: Peekable<SplitAsciiWhitespace<'a>>,
+    source: Source,
+    position: usize,
+}
+
+impl<'a> TokenReader<'a> {
+    pub fn new(text: &'a str, source: Source) -> Self {
+        Self {
+            tokens: text.split_ascii_whitespace().peekable(),
+            source,
+            position: 0,
+        }
+    }
+
+    pub fn token(&mut self) -> Result<&'a str, Verdict> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or_else(|| self.error(format!("unexpected EOF at token {}", self.position)))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    pub fn read<T: FromStr>(&mut self) -> Result<T, Verdict> {
+        let token = self.token()?;
+        token.parse().map_err(|_| {
+            self.error(format!(
+                "could not parse {:?} at token {} as {}",
+                token,
+                self.position - 1,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+
+    /// `min`以上`max`以下の値を読む
+    pub fn read_in_range<T: FromStr + PartialOrd + Display>(
+        &mut self,
+        min: T,
+        max: T,
+    ) -> Result<T, Verdict> {
+        let value = self.read::<T>()?;
+        if min <= value && value <= max {
+            Ok(value)
+        } else {
+            Err(self.error(format!(
+                "{value} at token {} is out of range [{min}, {max}]",
+                self.position - 1
+            )))
+        }
+    }
+
+    pub fn read_vec<T: FromStr>(&mut self, n: usize) -> Result<Vec<T>, Verdict> {
+        (0..n).map(|_| self.read()).collect()
+    }
+
+    pub fn is_eof(&mut self) -> bool {
+        self.tokens.peek().is_none()
+    }
+
+    /// 読み残したトークンがあればエラーとする
+    pub fn expect_eof(&mut self) -> CheckResult {
+        match self.tokens.peek().copied() {
+            None => Ok(()),
+            Some(token) => Err(self.error(format!(
+                "extra token {:?} at token {}",
+                token, self.position
+            ))),
+        }
+    }
+
+    fn error(&self, message: String) -> Verdict {
+        let message = format!("{}: {message}", self.source);
+        match self.source {
+            Source::Actual => Verdict::WrongAnswer(message),
+            Source::Input | Source::Expect => Verdict::Fail(message),
+        }
+    }
+}
+
+pub struct CheckerAssertion<'a> {
+    pub input: Cow<'a, str>,
+    pub expect: Cow<'a, str>,
+    pub checker: &'static dyn Checker,
+}
+
+impl Assertion for CheckerAssertion<'_> {
+    fn assert(&self, actual: &str) -> anyhow::Result<AssertResult> {
+        let result = self.checker.check(
+            &mut TokenReader::new(&self.input, Source::Input),
+            &mut TokenReader::new(&self.expect, Source::Expect),
+            &mut TokenReader::new(actual, Source::Actual),
+        );
+        match result {
+            Ok(()) => Ok(Ok(())),
+            Err(Verdict::WrongAnswer(message)) => Ok(Err(Diagnostic::Message(message))),
+            Err(Verdict::Fail(message)) => Err(anyhow::anyhow!(message)),
+        }
+    }
+}
+
+#[test]
+fn checker_test() {
+    /// 和がnになる正整数の組を1つ出力する
+    struct SumChecker;
+    impl Checker for SumChecker {
+        fn check(
+            &self,
+            input: &mut TokenReader,
+            _expect: &mut TokenReader,
+            actual: &mut TokenReader,
+        ) -> CheckResult {
+            let n = input.read::<u64>()?;
+            let a = actual.read_in_range(1, n)?;
+            let b = actual.read_in_range(1, n)?;
+            ensure(a + b == n, format!("{a} + {b} != {n}"))?;
+            actual.expect_eof()
+        }
+    }
+    let assert = |input: &str, actual: &str| {
+        CheckerAssertion {
+            input: Cow::Borrowed(input),
+            expect: Cow::Borrowed(""),
+            checker: &SumChecker,
+        }
+        .assert(actual)
+    };
+    assert!(assert("5", "2 3").unwrap().is_ok());
+    assert!(assert("5", "4\n1\n").unwrap().is_ok());
+    assert_eq!(
+        assert("5", "2 2").unwrap(),
+        Err(Diagnostic::Message("2 + 2 != 5".to_string()))
+    );
+    assert!(assert("5", "0 5").unwrap().is_err());
+    assert!(assert("5", "2").unwrap().is_err());
+    assert!(assert("5", "2 3 4").unwrap().is_err());
+    assert!(assert("5", "2 three").unwrap().is_err());
+    assert!(assert("five", "2 3").is_err());
+}
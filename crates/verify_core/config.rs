@@ -0,0 +1,198 @@
+use crate::calibration;
+use serde::Deserialize;
+use std::{num::NonZeroUsize, path::PathBuf, sync::OnceLock, thread};
+
+/// 環境変数の接頭辞 `RUST_JUDGE_JOBS`のように指定する
+const ENV_PREFIX: &str = "RUST_JUDGE_";
+
+/// 実行環境ごとに環境変数で指定する設定
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct VerifyConfig {
+    /// 同時に実行するケース数 未指定の場合はCPU数
+    /// 並列実行で実行時間の計測が不安定になる場合は1にして逐次実行する
+    pub jobs: Option<usize>,
+    /// TLEの判定に使う実行時間 `RUST_JUDGE_TIME_BASIS=cpu`のように指定する
+    #[serde(default)]
+    pub time_basis: TimeBasis,
+    /// 各ケースを繰り返し実行する回数 解答側で指定されていればそちらを使う
+    pub repeat: Option<usize>,
+    /// 繰り返し実行した場合にTLEの判定に使う統計量
+    #[serde(default)]
+    pub time_statistic: TimeStatistic,
+    /// ACでなかったケースの成果物を保存するディレクトリ 未指定の場合はキャッシュディレクトリ以下
+    pub artifacts_dir: Option<PathBuf>,
+    /// 実行時間制限に掛ける倍率 ジャッジより2倍遅い環境では`RUST_JUDGE_TIME_LIMIT_SCALE=2`とする
+    pub time_limit_scale: Option<f64>,
+    /// 基準の処理でこの環境の速さを計測し、実行時間制限に掛ける
+    #[serde(default)]
+    pub calibrate: bool,
+    /// 基準とする環境での基準の処理の実行時間(ms) 未指定の場合はワークスペースに保存したものを使う
+    pub calibration_baseline_ms: Option<f64>,
+    /// 計測結果を基準としてワークスペースに保存する 基準とする環境で1度だけ有効にする
+    #[serde(default)]
+    pub record_calibration_baseline: bool,
+    /// 実行時間制限の何%以内を制限付近とみなすか 未指定の場合は再実行も表示もしない
+    pub near_limit_margin: Option<f64>,
+    /// 制限付近でTLEとなったケースを追加で実行する回数
+    pub near_limit_retries: Option<usize>,
+    /// 判定するケース名のglob `*`と`?`が使え、`,`区切りで複数指定できる 未指定の場合は全て
+    pub cases: Option<String>,
+    /// 最初にACでなかったケースで判定を打ち切る
+    #[serde(default)]
+    pub fail_fast: bool,
+}
+
+/// TLEの判定に使う実行時間の種類
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeBasis {
+    /// 経過時間
+    #[default]
+    Wall,
+    /// 解答のユーザー時間とシステム時間の合計 負荷の高いCI環境での誤判定を避けられる
+    Cpu,
+}
+
+/// 繰り返し実行した実行時間のうち、判定に使うもの
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeStatistic {
+    Min,
+    /// 偶数回の場合は小さい方
+    #[default]
+    Median,
+    Max,
+}
+
+impl VerifyConfig {
+    /// 初回呼び出し時に環境変数から読み込む 不正な値があれば既定値を使う
+    pub fn get() -> &'static Self {
+        static CONFIG: OnceLock<VerifyConfig> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            envy::prefixed(ENV_PREFIX).from_env().unwrap_or_else(|e| {
+                println!("invalid {ENV_PREFIX}* environment variable: {e}");
+                Self::default()
+            })
+        })
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+            .max(1)
+    }
+
+    /// 実行時間制限の倍率 未指定か正の数でなければ1
+    pub fn time_limit_scale(&self) -> f64 {
+        self.time_limit_scale
+            .filter(|scale| scale.is_finite() && *scale > 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// 指定された倍率と、計測したこの環境の速さを合わせた倍率
+    pub fn time_limit_multiplier(&self) -> f64 {
+        self.time_limit_scale()
+            * calibration::get(self).map_or(1.0, |calibration| calibration.speed_factor())
+    }
+
+    /// 倍率を掛けた、実際に適用する実行時間制限
+    pub fn time_limit_ms(&self, time_limit_ms: u64) -> u64 {
+        (time_limit_ms as f64 * self.time_limit_multiplier()).round() as u64
+    }
+
+    /// 実行時間と倍率を掛けた実行時間制限の差が、制限の`near_limit_margin`%以内か
+    pub fn near_limit(&self, time_ms: u64, time_limit_ms: u64) -> bool {
+        let Some(margin) = self.near_limit_margin else {
+            return false;
+        };
+        let time_limit_ms = self.time_limit_ms(time_limit_ms) as f64;
+        (time_ms as f64 - time_limit_ms).abs() <= time_limit_ms * margin / 100.0
+    }
+
+    /// `cases`のうち、名前が指定されたglobのいずれかに一致するもの
+    pub fn select<'a, T>(&self, cases: &'a [T], name: impl Fn(&T) -> &str) -> Vec<&'a T> {
+        cases
+            .iter()
+            .filter(|case| match &self.cases {
+                Some(patterns) => patterns
+                    .split(',')
+                    .any(|pattern| glob_match(pattern.trim(), name(case))),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// `*`は任意の文字列、`?`は任意の1文字に一致する
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (
+        pattern.chars().collect::<Vec<_>>(),
+        name.chars().collect::<Vec<_>>(),
+    );
+    let (mut p, mut n) = (0, 0);
+    // 最後に見た`*`の位置と、そこから一致させ始めた位置
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // `*`に1文字多く一致させてやり直す
+                Some((sp, sn)) => {
+                    star = Some((sp, sn + 1));
+                    p = sp + 1;
+                    n = sn + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[test]
+fn time_limit_scale_test() {
+    let config = |scale| VerifyConfig {
+        time_limit_scale: Some(scale),
+        ..VerifyConfig::default()
+    };
+    assert_eq!(config(2.0).time_limit_ms(1500), 3000);
+    assert_eq!(config(0.5).time_limit_ms(1001), 501);
+    assert_eq!(config(-1.0).time_limit_ms(1500), 1500);
+    assert_eq!(VerifyConfig::default().time_limit_ms(1500), 1500);
+}
+
+#[test]
+fn near_limit_test() {
+    let config = VerifyConfig {
+        near_limit_margin: Some(5.0),
+        ..VerifyConfig::default()
+    };
+    assert!(config.near_limit(1040, 1000));
+    assert!(config.near_limit(960, 1000));
+    assert!(!config.near_limit(1060, 1000));
+    assert!(!VerifyConfig::default().near_limit(1000, 1000));
+}
+
+#[test]
+fn select_test() {
+    assert!(glob_match("*", ""));
+    assert!(glob_match("example_*", "example_00"));
+    assert!(glob_match("*random*.in", "max_random_01.in"));
+    assert!(glob_match("case?", "case1"));
+    assert!(!glob_match("case?", "case10"));
+    assert!(!glob_match("example_*", "large_00"));
+
+    let config = VerifyConfig {
+        cases: Some("example_*, max_?".to_string()),
+        ..VerifyConfig::default()
+    };
+    let cases = ["example_00", "random_00", "max_0", "max_00"];
+    assert_eq!(config.select(&cases, |s| s), [&"example_00", &"max_0"]);
+}
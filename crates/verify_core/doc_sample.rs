@@ -0,0 +1,90 @@
+use syn::{Attribute, Expr, ExprLit, Lit, Meta};
+
+/// ドキュメントコメントに書かれた入出力例
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub input: String,
+    pub output: String,
+}
+
+/// `#[doc = "..."]`属性を1つのドキュメントコメントにつなげる
+pub fn doc_comment(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 言語に`input`、`output`を指定したコードブロックを順に組にする
+pub fn parse(doc: &str) -> Result<Vec<Sample>, String> {
+    let mut samples = Vec::new();
+    let mut input = None;
+    let mut lines = doc.lines();
+    while let Some(line) = lines.next() {
+        let Some(info) = line.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        let kind = info.trim();
+        // 入出力例以外のコードブロックは読み飛ばす
+        let mut body = String::new();
+        for line in lines.by_ref() {
+            if line.trim_start().starts_with("```") {
+                break;
+            }
+            body.push_str(line);
+            body.push('\n');
+        }
+        match (kind, input.take()) {
+            ("input", None) => input = Some(body),
+            ("input", Some(_)) => return Err("input block is not followed by output block".into()),
+            ("output", Some(input)) => samples.push(Sample {
+                input,
+                output: body,
+            }),
+            ("output", None) => return Err("output block is not preceded by input block".into()),
+            (_, pending) => input = pending,
+        }
+    }
+    match input {
+        Some(_) => Err("input block is not followed by output block".into()),
+        None => Ok(samples),
+    }
+}
+
+#[test]
+fn parse_test() {
+    let attrs: Vec<Attribute> = vec![
+        syn::parse_quote!(#[doc = " # A + B"]),
+        syn::parse_quote!(#[doc = " ```input"]),
+        syn::parse_quote!(#[doc = " 1 2"]),
+        syn::parse_quote!(#[doc = " ```"]),
+        syn::parse_quote!(#[doc = " ```rust"]),
+        syn::parse_quote!(#[doc = " let x = 1;"]),
+        syn::parse_quote!(#[doc = " ```"]),
+        syn::parse_quote!(#[doc = " ```output"]),
+        syn::parse_quote!(#[doc = " 3"]),
+        syn::parse_quote!(#[doc = " ```"]),
+        syn::parse_quote!(#[derive(Debug)]),
+    ];
+    let doc = doc_comment(&attrs);
+    assert!(doc.starts_with("# A + B\n```input\n1 2\n```"));
+    assert_eq!(
+        parse(&doc).unwrap(),
+        [Sample {
+            input: "1 2\n".to_string(),
+            output: "3\n".to_string()
+        }]
+    );
+    assert!(parse("```input\n1\n```").is_err());
+    assert!(parse("```output\n1\n```").is_err());
+}
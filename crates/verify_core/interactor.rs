@@ -0,0 +1,77 @@
+use crate::checker::{CheckResult, TokenReader, Verdict};
+use std::{
+    fmt::{Debug, Formatter},
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+/// 解答者がRustで実装する、解答と対話しながら判定するインタラクター
+///
+/// `to_solver`に書き込んだ内容が解答の入力になり、解答の出力を`from_solver`から読む
+/// 解答は書き込みのたびに受け取るため、flushは不要
+/// `interact`から戻ると解答の入力は閉じられる
+pub trait Interactor: Sync {
+    fn interact(
+        &self,
+        input: &mut TokenReader,
+        expect: &mut TokenReader,
+        from_solver: &mut dyn BufRead,
+        to_solver: &mut dyn Write,
+    ) -> CheckResult;
+}
+
+impl Debug for dyn Interactor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Interactor")
+    }
+}
+
+/// 解答の出力から空白区切りのトークンを1つ読む
+/// 解答が出力を閉じた場合や読み込みに失敗した場合はWAとする
+pub fn read_from_solver<T: FromStr>(from_solver: &mut dyn BufRead) -> Result<T, Verdict> {
+    let mut token = Vec::new();
+    loop {
+        let buf = from_solver
+            .fill_buf()
+            .map_err(|e| Verdict::wrong_answer(format!("actual: {e}")))?;
+        if buf.is_empty() {
+            break;
+        }
+        let skip = if token.is_empty() {
+            buf.iter().take_while(|b| b.is_ascii_whitespace()).count()
+        } else {
+            0
+        };
+        let len = buf[skip..]
+            .iter()
+            .take_while(|b| !b.is_ascii_whitespace())
+            .count();
+        token.extend_from_slice(&buf[skip..skip + len]);
+        let done = skip + len < buf.len();
+        from_solver.consume(skip + len);
+        if done && !token.is_empty() {
+            break;
+        }
+    }
+    if token.is_empty() {
+        return Err(Verdict::wrong_answer("actual: unexpected EOF"));
+    }
+    let token = String::from_utf8_lossy(&token);
+    token.parse().map_err(|_| {
+        Verdict::wrong_answer(format!(
+            "actual: could not parse {:?} as {}",
+            token,
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+#[test]
+fn read_from_solver_test() {
+    let mut read = "  12 ab\n\n-3".as_bytes();
+    assert_eq!(read_from_solver::<u32>(&mut read), Ok(12));
+    assert_eq!(read_from_solver::<String>(&mut read), Ok("ab".to_string()));
+    assert_eq!(read_from_solver::<i32>(&mut read), Ok(-3));
+    assert!(read_from_solver::<i32>(&mut read).is_err());
+    assert!(read_from_solver::<i32>(&mut "x".as_bytes()).is_err());
+}
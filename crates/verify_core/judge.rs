@@ -1,12 +1,36 @@
-use crate::{attribute::VerifyAttribute, SolveFunc, Solver};
+pub mod diagnostic;
+pub mod interactive;
+pub mod isolated;
+pub mod panic_report;
+pub mod pool;
+
+use crate::{
+    artifact,
+    attribute::VerifyAttribute,
+    checker::CheckerAssertion,
+    config::{TimeBasis, TimeStatistic, VerifyConfig},
+    judge::{
+        diagnostic::{compare, lines, raw_lines, sorted, tokens, Diagnostic},
+        panic_report::PanicReport,
+    },
+    SolveFunc, Solver,
+};
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     fmt::{Display, Formatter, Result},
-    path::PathBuf,
+    fs,
+    future::Future,
+    io::{self, Read, Write},
+    panic,
+    path::{Path, PathBuf},
     process::Command,
-    time::Duration,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
 };
-use tokio::time;
+use tokio::{sync::oneshot, time};
 
 pub enum VerifyStatus {
     Accepted,
@@ -14,23 +38,78 @@ pub enum VerifyStatus {
     RuntimeError,
     InternalError,
     TimeLimitExceeded,
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
 }
 
 #[derive(Clone, Debug)]
 pub struct VerifyResult {
     pub cases: Vec<JudgeResult>,
+    /// 絞り込みや打ち切りで判定しなかったケース数
+    pub skipped: usize,
+}
+
+/// 問題全体の判定結果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Passed,
+    Failed,
+    /// テストケースが1つもない
+    NoTestCases,
+    /// テストケースはあるが、1つも判定しなかった
+    Skipped,
 }
 
 impl VerifyResult {
+    /// `total`個のケースのうち、判定した結果が`cases`
+    pub fn new(cases: Vec<JudgeResult>, total: usize) -> Self {
+        Self {
+            skipped: total.saturating_sub(cases.len()),
+            cases,
+        }
+    }
+
+    /// 別に判定したケースの結果を後ろに加える
+    pub fn merge(&mut self, other: VerifyResult) {
+        self.cases.extend(other.cases);
+        self.skipped += other.skipped;
+    }
+
+    pub fn outcome(&self) -> VerifyOutcome {
+        if self.cases.is_empty() {
+            if self.skipped == 0 {
+                VerifyOutcome::NoTestCases
+            } else {
+                VerifyOutcome::Skipped
+            }
+        } else if self.cases.iter().all(|c| c.status == JudgeStatus::Accepted) {
+            VerifyOutcome::Passed
+        } else {
+            VerifyOutcome::Failed
+        }
+    }
+
+    /// 1つ以上のケースを判定し、全てACだったか
     pub fn success(&self) -> bool {
-        self.cases.iter().all(|c| c.status == JudgeStatus::Accepted)
+        self.outcome() == VerifyOutcome::Passed
     }
 
     pub fn result_icon(&self) -> &'static str {
-        if self.success() {
-            "✅"
-        } else {
-            "❌"
+        match self.outcome() {
+            VerifyOutcome::Passed => "✅",
+            VerifyOutcome::Failed => "❌",
+            VerifyOutcome::NoTestCases | VerifyOutcome::Skipped => "⚠️",
+        }
+    }
+}
+
+impl Display for VerifyOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed => write!(f, "failed"),
+            Self::NoTestCases => write!(f, "no test cases"),
+            Self::Skipped => write!(f, "skipped"),
         }
     }
 }
@@ -39,7 +118,79 @@ impl VerifyResult {
 pub struct JudgeResult {
     pub status: JudgeStatus,
     pub name: String,
+    pub source: CaseSource,
     pub exec_time_ms: u64,
+    /// 解答のユーザー時間とシステム時間の合計 計測できない環境ではNone
+    pub cpu_time_ms: Option<u64>,
+    /// 繰り返し実行した場合の、TLEの判定に使う実行時間の分布
+    pub time_stats: Option<TimeStats>,
+    /// 実行時間が制限付近だった回があるか
+    pub near_limit: bool,
+    pub peak_memory_kb: Option<u64>,
+    pub message: Option<String>,
+    /// WAの場合の理由
+    pub diagnostic: Option<Diagnostic>,
+    /// 解答がpanicしたREの場合の情報
+    pub panic: Option<PanicReport>,
+    /// ACでなかった場合に保存した解答の出力やメッセージのファイル
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl JudgeResult {
+    pub fn new(name: String, status: JudgeStatus) -> Self {
+        Self {
+            status,
+            name,
+            source: CaseSource::Fetched,
+            exec_time_ms: 0,
+            cpu_time_ms: None,
+            time_stats: None,
+            near_limit: false,
+            peak_memory_kb: None,
+            message: None,
+            diagnostic: None,
+            panic: None,
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// プロセス内で実行した解答のTLEを記録する 打ち切ったスレッドが残っていればその数を添える
+    fn record_abandoned(&mut self) {
+        self.status = JudgeStatus::TimeLimitExceeded;
+        let left = left_running_solvers();
+        if left > 0 {
+            self.message = Some(format!("solver thread is left running ({left} in total)"));
+        }
+    }
+
+    /// 解答のpanicをREとして記録し、コンソールに表示する
+    fn record_panic(&mut self, report: PanicReport) {
+        println!("{}: {report}", self.name);
+        if let Some(backtrace) = &report.backtrace {
+            println!("{backtrace}");
+        }
+        self.status = JudgeStatus::RuntimeError;
+        self.message = Some(report.to_string());
+        self.panic = Some(report);
+    }
+}
+
+/// テストケースの置き場所
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaseSource {
+    /// サービスから取得し、キャッシュディレクトリに置いたケース
+    #[default]
+    Fetched,
+    /// リポジトリの`testcases`ディレクトリに置いたケース
+    Local,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeStats {
+    pub runs: usize,
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub max_ms: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -48,6 +199,10 @@ pub enum JudgeStatus {
     WrongAnswer,
     RuntimeError,
     TimeLimitExceeded,
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
+    /// 入力か想定解の出力のファイルがない
+    MissingFiles,
     InternalError,
 }
 impl Display for JudgeStatus {
@@ -58,129 +213,636 @@ impl Display for JudgeStatus {
             Self::RuntimeError => write!(f, "RE"),
             Self::InternalError => write!(f, "IE"),
             Self::TimeLimitExceeded => write!(f, "TLE"),
+            Self::MemoryLimitExceeded => write!(f, "MLE"),
+            Self::OutputLimitExceeded => write!(f, "OLE"),
+            Self::MissingFiles => write!(f, "MISSING"),
+        }
+    }
+}
+
+/// ケースの入力と想定解の出力を読む ファイルがなければMissingFilesの結果を返す
+pub fn read_case(
+    name: &str,
+    in_path: &Path,
+    out_path: &Path,
+) -> std::result::Result<(Vec<u8>, Vec<u8>), Box<JudgeResult>> {
+    match (fs::read(in_path), fs::read(out_path)) {
+        (Ok(input), Ok(expect)) => Ok((input, expect)),
+        (input, expect) => {
+            let missing = [(in_path, input.is_err()), (out_path, expect.is_err())]
+                .iter()
+                .filter(|(_, missing)| *missing)
+                .map(|(path, _)| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("{name}: case file is not found: {missing}");
+            let mut ret = JudgeResult::new(name.to_string(), JudgeStatus::MissingFiles);
+            ret.message = Some(format!("not found: {missing}"));
+            Err(Box::new(ret))
         }
     }
 }
 
+/// 解答の実行結果
+struct Execution {
+    termination: Termination,
+    elapsed: Duration,
+    cpu_time: Option<Duration>,
+    /// 隔離実行した場合のみ計測できる
+    peak_memory_kb: Option<u64>,
+}
+
+pub(crate) enum Termination {
+    Exited(Vec<u8>),
+    Panicked(PanicReport),
+    Aborted(String),
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
+    Timeout,
+}
+
 pub async fn verify_inner(
     name: String,
-    assertion: &StaticAssertion<'_>,
+    input: &[u8],
+    assertion: &dyn Assertion,
     attr: &VerifyAttribute,
     f: SolveFunc,
 ) -> JudgeResult {
-    let mut ret = JudgeResult {
-        name: name.clone(),
-        status: JudgeStatus::InternalError,
-        exec_time_ms: 0,
-    };
-    let run = async {
-        let now = time::Instant::now();
-        let actual = ::std::panic::catch_unwind(|| {
-            let mut actual = Vec::new();
-            f(assertion.input.as_bytes(), &mut actual);
-            actual
-        });
-        (actual, now.elapsed())
-    };
-    let sleep = time::sleep(Duration::from_millis(attr.time_limit_ms));
-    tokio::select! {
-        _ = sleep => {
-            // うまく動作していない 度を越えたTLEはこちらで打ち切りたい
-            ret.status = JudgeStatus::TimeLimitExceeded
-        },
-        (actual, elapsed) = run => {
-            ret.exec_time_ms = elapsed.as_millis() as u64;
-            if let Ok(actual) = actual {
-                match assertion.assert(&String::from_utf8_lossy(&actual)) {
-                    Ok(status) => {
-                        if status && ret.exec_time_ms <= attr.time_limit_ms {
-                            ret.status = JudgeStatus::Accepted
-                        } else if !status {
-                            ret.status = JudgeStatus::WrongAnswer
-                        } else {
-                            ret.status = JudgeStatus::TimeLimitExceeded
-                        }
-                    }
-                    Err(e) => {
-                        println!("{:?}", e);
-                        ret.status = JudgeStatus::InternalError
+    repeat(attr, || {
+        verify_once(name.clone(), input, assertion, attr, f)
+    })
+    .await
+}
+
+async fn verify_once(
+    name: String,
+    input: &[u8],
+    assertion: &dyn Assertion,
+    attr: &VerifyAttribute,
+    f: SolveFunc,
+) -> (JudgeResult, Option<Vec<u8>>) {
+    let mut ret = JudgeResult::new(name, JudgeStatus::InternalError);
+    let limit = hard_time_limit(attr);
+    let execution = if attr.isolated {
+        isolated::execute(input, attr, limit).await
+    } else {
+        execute(f, input, attr, limit).await
+    };
+    let execution = match execution {
+        Ok(execution) => execution,
+        Err(e) => {
+            println!("{:?}", e);
+            ret.message = Some(e.to_string());
+            return (ret, None);
+        }
+    };
+    ret.exec_time_ms = execution.elapsed.as_millis() as u64;
+    ret.cpu_time_ms = execution.cpu_time.map(|t| t.as_millis() as u64);
+    ret.peak_memory_kb = execution.peak_memory_kb;
+    if ret
+        .peak_memory_kb
+        .is_some_and(|kb| kb > attr.memory_limit_mb * 1024)
+    {
+        ret.status = JudgeStatus::MemoryLimitExceeded;
+        return (ret, None);
+    }
+    let mut actual = None;
+    match execution.termination {
+        Termination::Exited(output) => {
+            match assertion.assert(&String::from_utf8_lossy(actual.insert(output))) {
+                Ok(Ok(())) => {
+                    if within_time_limit(&ret, attr) {
+                        ret.status = JudgeStatus::Accepted
+                    } else {
+                        ret.status = JudgeStatus::TimeLimitExceeded
                     }
                 }
-            } else {
-                ret.status = JudgeStatus::RuntimeError
+                Ok(Err(diagnostic)) => {
+                    println!("{}: {diagnostic}", ret.name);
+                    ret.status = JudgeStatus::WrongAnswer;
+                    ret.diagnostic = Some(diagnostic);
+                }
+                Err(e) => {
+                    println!("{:?}", e);
+                    ret.status = JudgeStatus::InternalError;
+                    ret.message = Some(e.to_string());
+                }
             }
-        },
+        }
+        Termination::Panicked(report) => ret.record_panic(report),
+        Termination::Aborted(reason) => {
+            ret.status = JudgeStatus::RuntimeError;
+            ret.message = Some(reason);
+        }
+        Termination::MemoryLimitExceeded => ret.status = JudgeStatus::MemoryLimitExceeded,
+        Termination::OutputLimitExceeded => ret.status = JudgeStatus::OutputLimitExceeded,
+        Termination::Timeout if attr.isolated => ret.status = JudgeStatus::TimeLimitExceeded,
+        Termination::Timeout => ret.record_abandoned(),
     }
+    (ret, actual)
+}
+
+/// TLE判定後、打ち切るまでに待つ猶予時間
+const TIME_LIMIT_GRACE_MILLIS: u64 = 1000;
+/// CPU時間で判定する場合、実行時間制限の何倍の経過時間で打ち切るか
+const CPU_TIME_WALL_RATIO: u64 = 2;
+
+/// 解答を打ち切るまでの経過時間
+fn hard_time_limit(attr: &VerifyAttribute) -> Duration {
+    let config = VerifyConfig::get();
+    let time_limit_ms = config.time_limit_ms(attr.time_limit_ms);
+    let time_limit_ms = match config.time_basis {
+        TimeBasis::Wall => time_limit_ms,
+        // 負荷の高い環境では経過時間がCPU時間より大きく伸びるため、余裕を持たせる
+        TimeBasis::Cpu => time_limit_ms * CPU_TIME_WALL_RATIO,
+    };
+    Duration::from_millis(time_limit_ms + TIME_LIMIT_GRACE_MILLIS)
+}
+
+/// 設定された基準の実行時間 CPU時間を計測できなければ経過時間を使う
+fn judged_time_ms(result: &JudgeResult) -> u64 {
+    match VerifyConfig::get().time_basis {
+        TimeBasis::Wall => result.exec_time_ms,
+        TimeBasis::Cpu => result.cpu_time_ms.unwrap_or(result.exec_time_ms),
+    }
+}
+
+/// 設定された基準の実行時間が、倍率を掛けた制限内か
+fn within_time_limit(result: &JudgeResult, attr: &VerifyAttribute) -> bool {
+    judged_time_ms(result) <= VerifyConfig::get().time_limit_ms(attr.time_limit_ms)
+}
+
+/// 解答側の指定、設定の順に決まる繰り返し実行の回数
+fn repeat_count(attr: &VerifyAttribute) -> usize {
+    attr.repeat
+        .or(VerifyConfig::get().repeat)
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// 正しく出力できている間は`run`を繰り返し、設定された統計量の実行時間だった回の結果を返す
+/// `run`は判定結果と解答の出力を返し、ACでなかった回は成果物として保存する
+async fn repeat<F: Future<Output = (JudgeResult, Option<Vec<u8>>)>>(
+    attr: &VerifyAttribute,
+    mut run: impl FnMut() -> F,
+) -> JudgeResult {
+    let mut results = Vec::new();
+    let mut runs = repeat_count(attr);
+    loop {
+        let (mut result, output) = run().await;
+        if result.status != JudgeStatus::Accepted {
+            if let Some(dir) = &attr.artifact_dir {
+                artifact::save(&mut result, output.as_deref(), dir);
+            }
+        }
+        // 制限付近でのTLEは計測のぶれによることがあるため、計り直す
+        if results.is_empty()
+            && result.status == JudgeStatus::TimeLimitExceeded
+            && near_limit(&result, attr)
+        {
+            runs = runs.max(1 + VerifyConfig::get().near_limit_retries.unwrap_or(2));
+        }
+        results.push(result);
+        if results.len() >= runs || !timed(results.last().unwrap(), attr) {
+            break;
+        }
+    }
+    let near = results
+        .iter()
+        .any(|r| timed(r, attr) && near_limit(r, attr));
+    let mut ret = summarize(results, attr);
+    if near {
+        println!(
+            "{}: near the time limit ({}ms / {}ms)",
+            ret.name,
+            judged_time_ms(&ret),
+            VerifyConfig::get().time_limit_ms(attr.time_limit_ms)
+        );
+        ret.near_limit = true;
+    }
+    ret
+}
+
+/// 設定された基準の実行時間が制限付近か
+fn near_limit(result: &JudgeResult, attr: &VerifyAttribute) -> bool {
+    VerifyConfig::get().near_limit(judged_time_ms(result), attr.time_limit_ms)
+}
+
+/// 実行時間を計り直す意味がある結果か 打ち切った場合は繰り返さない
+fn timed(result: &JudgeResult, attr: &VerifyAttribute) -> bool {
+    match result.status {
+        JudgeStatus::Accepted => true,
+        JudgeStatus::TimeLimitExceeded => {
+            (result.exec_time_ms as u128) < hard_time_limit(attr).as_millis()
+        }
+        _ => false,
+    }
+}
+
+fn summarize(mut results: Vec<JudgeResult>, attr: &VerifyAttribute) -> JudgeResult {
+    if results.len() == 1 {
+        return results.pop().unwrap();
+    }
+    // 実行時間以外の理由で失敗した回があればその結果を返す
+    if let Some(i) = results.iter().position(|r| !timed(r, attr)) {
+        return results.swap_remove(i);
+    }
+    results.sort_by_key(judged_time_ms);
+    let peak_memory_kb = results.iter().filter_map(|r| r.peak_memory_kb).max();
+    let time_stats = TimeStats {
+        runs: results.len(),
+        min_ms: judged_time_ms(&results[0]),
+        median_ms: judged_time_ms(&results[(results.len() - 1) / 2]),
+        max_ms: judged_time_ms(&results[results.len() - 1]),
+    };
+    let i = match VerifyConfig::get().time_statistic {
+        TimeStatistic::Min => 0,
+        TimeStatistic::Median => (results.len() - 1) / 2,
+        TimeStatistic::Max => results.len() - 1,
+    };
+    let mut ret = results.swap_remove(i);
+    ret.status = if within_time_limit(&ret, attr) {
+        JudgeStatus::Accepted
+    } else {
+        JudgeStatus::TimeLimitExceeded
+    };
+    ret.peak_memory_kb = peak_memory_kb;
+    ret.time_stats = Some(time_stats);
     ret
 }
 
+/// 解答の書き込み先、経過時間、CPU時間
+type SolveOutput<W> = (thread::Result<W>, Duration, Option<Duration>);
+
+/// TLEで打ち切った後も実行が続いている、プロセス内の解答スレッドの数
+static LEFT_RUNNING_SOLVERS: AtomicUsize = AtomicUsize::new(0);
+
+/// TLEで打ち切った後も実行が続いている解答スレッドの数
+/// スレッドは止められないため、隔離実行しない場合はテストの終了まで残ることがある
+pub fn left_running_solvers() -> usize {
+    LEFT_RUNNING_SOLVERS.load(Ordering::SeqCst)
+}
+
+/// 解答を別スレッドで実行する
+async fn execute(
+    f: SolveFunc,
+    input: &[u8],
+    attr: &VerifyAttribute,
+    limit: Duration,
+) -> anyhow::Result<Execution> {
+    let run = spawn_solver(
+        f,
+        io::Cursor::new(input.to_vec()),
+        Vec::new(),
+        attr.stack_size,
+        attr.output_limit_bytes,
+    )?;
+    wait_solver(run, limit, |output| output).await
+}
+
+/// 解答を別スレッドで実行し、`limit`で打ち切る 出力は`write`に書き込む
+/// 打ち切った場合やpanicした場合も、それまでの出力は`write`に残る
+pub(crate) async fn run_solver<W: Write + Send + 'static>(
+    f: SolveFunc,
+    input: &[u8],
+    write: W,
+    stack_size: usize,
+    output_limit: usize,
+    limit: Duration,
+) -> anyhow::Result<Termination> {
+    let run = spawn_solver(
+        f,
+        io::Cursor::new(input.to_vec()),
+        write,
+        stack_size,
+        output_limit,
+    )?;
+    Ok(wait_solver(run, limit, |_| Vec::new()).await?.termination)
+}
+
+/// 解答を別スレッドで実行し、書き込み先と実行時間を受け取るチャネルを返す
+fn spawn_solver<R, W>(
+    f: SolveFunc,
+    mut read: R,
+    write: W,
+    stack_size: usize,
+    output_limit: usize,
+) -> io::Result<oneshot::Receiver<SolveOutput<W>>>
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    thread::Builder::new()
+        .name("solver".to_string())
+        .stack_size(stack_size)
+        .spawn(move || {
+            let now = Instant::now();
+            let cpu_time = thread_cpu_time();
+            let actual = panic_report::catch(|| {
+                let mut write = LimitedWriter::new(write, output_limit);
+                f(&mut read, &mut write);
+                write.inner
+            });
+            let cpu_time = thread_cpu_time()
+                .zip(cpu_time)
+                .map(|(end, start)| end.saturating_sub(start));
+            // 打ち切られていれば送れないため、残っていたスレッドとして差し引く
+            if tx.send((actual, now.elapsed(), cpu_time)).is_err() {
+                LEFT_RUNNING_SOLVERS
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .ok();
+            }
+        })?;
+    Ok(rx)
+}
+
+/// 解答の終了を待つ 書き込み先は`output`で解答の出力に変換する
+async fn wait_solver<W>(
+    mut run: oneshot::Receiver<SolveOutput<W>>,
+    limit: Duration,
+    output: impl FnOnce(W) -> Vec<u8>,
+) -> anyhow::Result<Execution> {
+    let (termination, elapsed, cpu_time) = match time::timeout(limit, &mut run).await {
+        Ok(result) => match result? {
+            (Ok(write), elapsed, cpu_time) => {
+                (Termination::Exited(output(write)), elapsed, cpu_time)
+            }
+            (Err(payload), elapsed, cpu_time) if payload.is::<OutputLimitExceeded>() => {
+                (Termination::OutputLimitExceeded, elapsed, cpu_time)
+            }
+            (Err(payload), elapsed, cpu_time) => match payload.downcast::<PanicReport>() {
+                Ok(report) => (Termination::Panicked(*report), elapsed, cpu_time),
+                Err(_) => (
+                    Termination::Aborted("panicked".to_string()),
+                    elapsed,
+                    cpu_time,
+                ),
+            },
+        },
+        Err(_) => {
+            // 解答スレッドは止められないため、結果を待たずに打ち切り、残ったスレッドとして数える
+            // 受信を閉じる前に終了していれば、結果が届いているので数え直す
+            LEFT_RUNNING_SOLVERS.fetch_add(1, Ordering::SeqCst);
+            run.close();
+            if run.try_recv().is_ok() {
+                LEFT_RUNNING_SOLVERS.fetch_sub(1, Ordering::SeqCst);
+            }
+            (Termination::Timeout, limit, None)
+        }
+    };
+    Ok(Execution {
+        termination,
+        elapsed,
+        cpu_time,
+        peak_memory_kb: None,
+    })
+}
+
+/// 呼び出したスレッドがこれまでに使ったCPU時間
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    match unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) } {
+        0 => Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32)),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+/// 出力が上限を超えたことを表すpanicのペイロード
+struct OutputLimitExceeded;
+
+/// 書き込みが上限を超えたら解答を打ち切るWriter
+struct LimitedWriter<W> {
+    inner: W,
+    written: usize,
+    limit: usize,
+    exceeded: bool,
+}
+
+impl<W> LimitedWriter<W> {
+    fn new(inner: W, limit: usize) -> Self {
+        Self {
+            inner,
+            written: 0,
+            limit,
+            exceeded: false,
+        }
+    }
+}
+
+impl<W: Write> Write for LimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.exceeded {
+            // 巻き戻し中のflushなどで再度panicしないよう、2回目以降はエラーを返す
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "output limit exceeded",
+            ));
+        }
+        if self.written + buf.len() > self.limit {
+            // 書き込みエラーを無視して出力し続ける解答も止めるため、巻き戻して脱出する
+            self.exceeded = true;
+            panic::resume_unwind(Box::new(OutputLimitExceeded));
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 出力が正しければ`Ok(())`、誤っていればその理由
+pub type AssertResult = std::result::Result<(), Diagnostic>;
+
 pub trait Assertion {
-    fn assert(&self, actual: &str) -> anyhow::Result<bool>;
+    /// 判定できなかった場合は`Err`を返し、IEとなる
+    fn assert(&self, actual: &str) -> anyhow::Result<AssertResult>;
+}
+
+/// 期待される出力と解答の出力の比較方法
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareMode {
+    /// バイト列として完全に一致する
+    Exact,
+    /// 行ごとに比較する 行末の空白と末尾の空行は無視する
+    Line,
+    /// 空白区切りのトークンごとに比較する EPSILONが指定されていれば数値は誤差を許容する
+    #[default]
+    Token,
+    /// トークンごとに大文字小文字を区別せずに比較する
+    TokenIgnoreCase,
+    /// トークンを浮動小数点数として誤差を許容して比較する
+    Float,
+    /// 行の順序を問わず、行の多重集合として比較する
+    UnorderedLines,
+    /// トークンの順序を問わず、トークンの多重集合として比較する
+    UnorderedTokens,
+}
+
+impl FromStr for CompareMode {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "exact" => Ok(Self::Exact),
+            "line" => Ok(Self::Line),
+            "token" => Ok(Self::Token),
+            "token_ignore_case" => Ok(Self::TokenIgnoreCase),
+            "float" => Ok(Self::Float),
+            "unordered_lines" => Ok(Self::UnorderedLines),
+            "unordered_tokens" => Ok(Self::UnorderedTokens),
+            _ => Err(anyhow::anyhow!("unknown compare mode: {s}")),
+        }
+    }
+}
+
+/// 1ケース分を判定する インタラクターが指定されていれば解答と対話させる
+pub async fn verify_case(
+    name: String,
+    input: &[u8],
+    expect: &[u8],
+    attr: &VerifyAttribute,
+    f: SolveFunc,
+) -> JudgeResult {
+    match attr.interactor {
+        Some(interactor) => {
+            let interaction = interactive::Interaction::Native(interactor);
+            interactive::verify_inner(name, input, expect, interaction, attr, f).await
+        }
+        None => {
+            let assertion = assertion(
+                String::from_utf8_lossy(input),
+                String::from_utf8_lossy(expect),
+                attr,
+            );
+            verify_inner(name, input, assertion.as_ref(), attr, f).await
+        }
+    }
 }
 
+/// 解答者がチェッカーを指定していればそれを、なければ比較方法に従って判定するAssertionを作る
+pub fn assertion<'a>(
+    input: Cow<'a, str>,
+    expect: Cow<'a, str>,
+    attr: &VerifyAttribute,
+) -> Box<dyn Assertion + 'a> {
+    match attr.checker {
+        Some(checker) => Box::new(CheckerAssertion {
+            input,
+            expect,
+            checker,
+        }),
+        None => Box::new(StaticAssertion {
+            input,
+            expect,
+            eps: attr.epsilon,
+            mode: attr.compare_mode.unwrap_or_default(),
+        }),
+    }
+}
+
+/// 解答`S`の定数に従って判定方法を選ぶ `assertion`と同じくチェッカーを優先する
+pub fn solver_assertion<'a, S: Solver + ?Sized>(
+    input: Cow<'a, str>,
+    expect: Cow<'a, str>,
+) -> Box<dyn Assertion + 'a> {
+    match S::CHECKER {
+        Some(checker) => Box::new(CheckerAssertion {
+            input,
+            expect,
+            checker,
+        }),
+        None => Box::new(StaticAssertion {
+            input,
+            expect,
+            eps: S::EPSILON,
+            mode: S::COMPARE_MODE.unwrap_or_default(),
+        }),
+    }
+}
+
+/// 解答`S`を実行し、出力が誤っていればpanicする
+pub fn assert_solver<S: Solver + ?Sized>(input: &str, expect: &str) {
+    let mut buf = Vec::new();
+    S::solve(input.as_bytes(), &mut buf);
+    let assert = solver_assertion::<S>(Cow::Borrowed(input), Cow::Borrowed(expect));
+    if let Err(diagnostic) = assert.assert(&String::from_utf8_lossy(&buf)).expect("") {
+        panic!("{diagnostic}")
+    }
+}
+
+/// Float指定でEPSILONが未指定の場合に許容する誤差
+const DEFAULT_EPSILON: f64 = 1e-9;
+
 pub struct StaticAssertion<'a> {
     pub input: Cow<'a, str>,
     pub expect: Cow<'a, str>,
     pub eps: Option<f64>,
+    pub mode: CompareMode,
 }
 impl Assertion for StaticAssertion<'_> {
-    fn assert(&self, actual: &str) -> anyhow::Result<bool> {
-        let expect_values = self
-            .expect
-            .split_ascii_whitespace()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>();
-        let actual_values = actual
-            .split_ascii_whitespace()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>();
-
-        if expect_values.len() != actual_values.len() {
-            println!("expect: {:?}\nactual: {:?}", expect_values, actual_values);
-            Ok(false)
-        } else {
-            for (expect, actual) in expect_values.iter().zip(&actual_values) {
-                if let Some(eps) = self.eps {
-                    match (expect.parse::<f64>(), actual.parse::<f64>()) {
-                        (Ok(ex), Ok(ac)) => {
-                            if !((ex - ac).abs() <= eps || ((ex - ac) / ex).abs() <= eps) {
-                                println!(
-                                    "expect: {:?}\nactual: {:?}",
-                                    expect_values, actual_values
-                                );
-                                return Ok(false);
-                            }
-                        }
-                        _ => {
-                            if expect != actual {
-                                println!(
-                                    "expect: {:?}\nactual: {:?}",
-                                    expect_values, actual_values
-                                );
-                                return Ok(false);
-                            }
-                        }
-                    }
-                } else if expect != actual {
-                    println!("expect: {:?}\nactual: {:?}", expect_values, actual_values);
-                    return Ok(false);
-                }
+    fn assert(&self, actual: &str) -> anyhow::Result<AssertResult> {
+        let expect = self.expect.as_ref();
+        let eq = |ex: &str, ac: &str| ex == ac;
+        let result = match self.mode {
+            CompareMode::Exact => compare("line", &raw_lines(expect), &raw_lines(actual), eq),
+            CompareMode::Line => compare("line", &lines(expect), &lines(actual), eq),
+            CompareMode::Token => match self.eps {
+                Some(eps) => compare("token", &tokens(expect), &tokens(actual), |ex, ac| {
+                    float_eq(ex, ac, eps)
+                }),
+                None => compare("token", &tokens(expect), &tokens(actual), eq),
+            },
+            CompareMode::TokenIgnoreCase => {
+                compare("token", &tokens(expect), &tokens(actual), |ex, ac| {
+                    ex.eq_ignore_ascii_case(ac)
+                })
+            }
+            CompareMode::Float => {
+                let eps = self.eps.unwrap_or(DEFAULT_EPSILON);
+                compare("token", &tokens(expect), &tokens(actual), |ex, ac| {
+                    float_eq(ex, ac, eps)
+                })
+            }
+            CompareMode::UnorderedLines => {
+                compare("line", &sorted(lines(expect)), &sorted(lines(actual)), eq)
             }
-            Ok(true)
+            CompareMode::UnorderedTokens => compare(
+                "token",
+                &sorted(tokens(expect)),
+                &sorted(tokens(actual)),
+                eq,
+            ),
+        };
+        match result {
+            Some(mismatch) => Ok(Err(Diagnostic::Mismatch(Box::new(mismatch)))),
+            None => Ok(Ok(())),
         }
     }
 }
 
+fn float_eq(expect: &str, actual: &str, eps: f64) -> bool {
+    match (expect.parse::<f64>(), actual.parse::<f64>()) {
+        (Ok(ex), Ok(ac)) => (ex - ac).abs() <= eps || ((ex - ac) / ex).abs() <= eps,
+        _ => expect == actual,
+    }
+}
+
 impl<'a> StaticAssertion<'a> {
+    /// 解答`S`を実行し、誤っていればpanicする チェッカーが指定されていればそれで判定する
     pub fn equals<S: Solver>(input: &'a str, expect: &'a str) {
-        let mut buf = Vec::new();
-        S::solve(input.as_bytes(), &mut buf);
-        let assert = Self {
-            input: Cow::Borrowed(input),
-            expect: Cow::Borrowed(expect),
-            eps: S::EPSILON,
-        };
-        assert!(assert.assert(&String::from_utf8_lossy(&buf)).expect(""))
+        assert_solver::<S>(input, expect)
     }
 }
 
@@ -191,7 +853,7 @@ pub struct CheckBinaryAssertion {
 }
 
 impl Assertion for CheckBinaryAssertion {
-    fn assert(&self, actual: &str) -> anyhow::Result<bool> {
+    fn assert(&self, actual: &str) -> anyhow::Result<AssertResult> {
         if !self.checker_path.exists() {
             println!(
                 "checker file is not found {}",
@@ -208,8 +870,16 @@ impl Assertion for CheckBinaryAssertion {
             ])
             .output()?;
         match output.status.code() {
-            Some(0) => Ok(true),
-            _ => Ok(false),
+            Some(0) => Ok(Ok(())),
+            // testlibのチェッカーは理由を標準エラー出力に書く
+            _ => {
+                let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                Ok(Err(Diagnostic::Message(if message.is_empty() {
+                    format!("checker {}", output.status)
+                } else {
+                    message
+                })))
+            }
         }
     }
 }
@@ -220,35 +890,466 @@ fn assert_test() {
         input: Cow::Owned("".into()),
         expect: Cow::Owned("123".into()),
         eps: None,
+        mode: CompareMode::Token,
     }
     .assert("123");
-    assert!(res.unwrap());
+    assert!(res.unwrap().is_ok());
     let res = StaticAssertion {
         input: Cow::Owned("".into()),
         expect: Cow::Owned("123".into()),
         eps: None,
+        mode: CompareMode::Token,
     }
     .assert("124");
-    assert!(!res.unwrap());
+    assert!(res.unwrap().is_err());
     let res = StaticAssertion {
         input: Cow::Owned("".into()),
         expect: Cow::Owned("10000".into()),
         eps: Some(1e-4),
+        mode: CompareMode::Token,
     }
     .assert("10001");
-    assert!(res.unwrap());
+    assert!(res.unwrap().is_ok());
     let res = StaticAssertion {
         input: Cow::Owned("".into()),
         expect: Cow::Owned("10000".into()),
         eps: Some(1e-4),
+        mode: CompareMode::Token,
     }
     .assert("-10000");
-    assert!(!res.unwrap());
+    assert!(res.unwrap().is_err());
     let res = StaticAssertion {
         input: Cow::Owned("".into()),
         expect: Cow::Owned("10000".into()),
         eps: Some(1e-5),
+        mode: CompareMode::Token,
     }
     .assert("10001");
-    assert!(!res.unwrap());
+    assert!(res.unwrap().is_err());
+}
+
+#[test]
+fn compare_mode_test() {
+    let assert = |mode, expect: &str, actual: &str| {
+        StaticAssertion {
+            input: Cow::Borrowed(""),
+            expect: Cow::Borrowed(expect),
+            eps: None,
+            mode,
+        }
+        .assert(actual)
+        .unwrap()
+        .is_ok()
+    };
+    assert!(assert(CompareMode::Exact, "1 2\n", "1 2\n"));
+    assert!(!assert(CompareMode::Exact, "1 2\n", "1 2"));
+    assert!(assert(CompareMode::Line, "1 2\n3\n", "1 2  \r\n3\n\n"));
+    assert!(!assert(CompareMode::Line, "1 2\n3\n", "1\n2 3\n"));
+    assert!(assert(CompareMode::Token, "1 2\n3\n", "1\n2 3\n"));
+    assert!(!assert(CompareMode::Token, "Yes", "YES"));
+    assert!(assert(CompareMode::TokenIgnoreCase, "Yes", "YES"));
+    assert!(assert(CompareMode::Float, "0.5", "0.5000000001"));
+    assert!(!assert(CompareMode::Float, "0.5", "0.50001"));
+    assert!(assert(
+        CompareMode::UnorderedLines,
+        "1 2\n3 4\n",
+        "3 4\n1 2\n"
+    ));
+    assert!(!assert(
+        CompareMode::UnorderedLines,
+        "1 2\n3 4\n",
+        "2 1\n3 4\n"
+    ));
+    assert!(!assert(
+        CompareMode::UnorderedLines,
+        "1\n1\n2\n",
+        "1\n2\n2\n"
+    ));
+    assert!(assert(
+        CompareMode::UnorderedTokens,
+        "1 2\n3 4\n",
+        "4 3 2\n1\n"
+    ));
+    assert!(!assert(CompareMode::UnorderedTokens, "1 1 2", "1 2 2"));
+}
+
+#[cfg(test)]
+pub(crate) fn test_attr(entry_point: Option<&str>) -> VerifyAttribute {
+    VerifyAttribute {
+        problem_id: String::new(),
+        epsilon: None,
+        time_limit_ms: 2000,
+        memory_limit_mb: 1024,
+        stack_size: 1 << 20,
+        output_limit_bytes: 1 << 20,
+        compare_mode: None,
+        repeat: None,
+        checker: None,
+        interactor: None,
+        artifact_dir: None,
+        isolated: entry_point.is_some(),
+        entry_point: entry_point.map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+fn test_judge(attr: &VerifyAttribute, f: SolveFunc) -> JudgeResult {
+    let assertion = StaticAssertion {
+        input: Cow::Owned("".into()),
+        expect: Cow::Owned("".into()),
+        eps: None,
+        mode: CompareMode::Token,
+    };
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_inner("test".into(), &[], &assertion, attr, f))
+}
+
+#[test]
+fn time_limit_test() {
+    fn never_returns(_read: &mut dyn Read, _write: &mut dyn Write) {
+        loop {
+            thread::park();
+        }
+    }
+    let attr = VerifyAttribute {
+        time_limit_ms: 100,
+        ..test_attr(None)
+    };
+    let res = test_judge(&attr, never_returns);
+    assert_eq!(res.status, JudgeStatus::TimeLimitExceeded);
+}
+
+#[test]
+fn output_limit_test() {
+    fn prints_forever(_read: &mut dyn Read, write: &mut dyn Write) {
+        let mut write = std::io::BufWriter::new(write);
+        loop {
+            writeln!(write, "Hello World").ok();
+        }
+    }
+    let res = test_judge(&test_attr(None), prints_forever);
+    assert_eq!(res.status, JudgeStatus::OutputLimitExceeded);
+}
+
+#[test]
+fn isolated_exit_entry() {
+    fn exits_abnormally(_read: &mut dyn Read, _write: &mut dyn Write) {
+        std::process::exit(3)
+    }
+    isolated::serve_if_child(exits_abnormally, &test_attr(None))
+}
+
+#[test]
+fn isolated_test() {
+    let attr = test_attr(Some("verify_core::judge::isolated_exit_entry"));
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::RuntimeError);
+    assert_eq!(res.message.as_deref(), Some("exit status: 3"));
+}
+
+#[test]
+fn isolated_timeout_entry() {
+    fn loops(_read: &mut dyn Read, _write: &mut dyn Write) {
+        loop {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    isolated::serve_if_child(loops, &test_attr(None))
+}
+
+#[test]
+fn isolated_timeout_test() {
+    let attr = VerifyAttribute {
+        time_limit_ms: 200,
+        ..test_attr(Some("verify_core::judge::isolated_timeout_entry"))
+    };
+    let now = Instant::now();
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::TimeLimitExceeded);
+    assert!(now.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn isolated_missing_entry_test() {
+    let attr = test_attr(Some("verify_core::judge::no_such_entry"));
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::InternalError);
+    assert!(res
+        .message
+        .unwrap()
+        .contains("verify_core::judge::no_such_entry"));
+}
+
+#[test]
+fn isolated_memory_entry() {
+    fn allocates(_read: &mut dyn Read, write: &mut dyn Write) {
+        let v = std::hint::black_box(vec![1u8; 24 << 20]);
+        write.write_all(&v[..1]).ok();
+    }
+    isolated::serve_if_child(allocates, &test_attr(None))
+}
+
+#[test]
+fn memory_limit_test() {
+    let attr = VerifyAttribute {
+        memory_limit_mb: 16,
+        ..test_attr(Some("verify_core::judge::isolated_memory_entry"))
+    };
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::MemoryLimitExceeded);
+    assert!(res.peak_memory_kb.unwrap() > 16 * 1024);
+}
+
+#[test]
+fn isolated_large_stack_entry() {
+    let attr = VerifyAttribute {
+        stack_size: 100 << 20,
+        ..test_attr(None)
+    };
+    isolated::serve_if_child(|_, write| write.write_all(b"ok").unwrap(), &attr)
+}
+
+#[test]
+fn isolated_huge_stack_entry() {
+    let attr = VerifyAttribute {
+        stack_size: 1 << 40,
+        ..test_attr(None)
+    };
+    isolated::serve_if_child(|_, _| (), &attr)
+}
+
+#[test]
+fn isolated_stack_limit_test() {
+    // メモリ制限が小さくても、スタックの分は確保できる
+    let attr = VerifyAttribute {
+        memory_limit_mb: 16,
+        stack_size: 100 << 20,
+        ..test_attr(Some("verify_core::judge::isolated_large_stack_entry"))
+    };
+    let assertion = StaticAssertion {
+        input: Cow::Borrowed(""),
+        expect: Cow::Borrowed("ok"),
+        eps: None,
+        mode: CompareMode::default(),
+    };
+    let res = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_inner(
+            "test".into(),
+            &[],
+            &assertion,
+            &attr,
+            |_, _| (),
+        ));
+    assert_eq!(res.status, JudgeStatus::Accepted);
+
+    let attr = VerifyAttribute {
+        memory_limit_mb: 16,
+        ..test_attr(Some("verify_core::judge::isolated_huge_stack_entry"))
+    };
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::InternalError);
+    assert!(res
+        .message
+        .unwrap()
+        .starts_with("could not spawn solver thread"));
+}
+
+#[test]
+fn isolated_stack_overflow_entry() {
+    fn recurse(depth: u64) -> u64 {
+        if depth == 0 {
+            return 0;
+        }
+        std::hint::black_box([0u8; 1024]);
+        std::hint::black_box(recurse(depth - 1)) + 1
+    }
+    fn overflows(_read: &mut dyn Read, write: &mut dyn Write) {
+        write.write_all(&[recurse(u64::MAX) as u8]).ok();
+    }
+    isolated::serve_if_child(overflows, &test_attr(None))
+}
+
+#[test]
+fn stack_overflow_test() {
+    let attr = test_attr(Some("verify_core::judge::isolated_stack_overflow_entry"));
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::RuntimeError);
+    assert!(res.message.unwrap().starts_with("stack overflow"));
+}
+
+#[test]
+fn cpu_time_test() {
+    fn sleeps(_read: &mut dyn Read, _write: &mut dyn Write) {
+        thread::sleep(Duration::from_millis(300));
+    }
+    let res = test_judge(&test_attr(None), sleeps);
+    assert!(res.exec_time_ms >= 300);
+    if cfg!(unix) {
+        assert!(res.cpu_time_ms.unwrap() < 100);
+    }
+}
+
+#[test]
+fn repeat_test() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static RUNS: AtomicU64 = AtomicU64::new(0);
+    // 1回目だけ遅い
+    fn slow_once(_read: &mut dyn Read, _write: &mut dyn Write) {
+        if RUNS.fetch_add(1, Ordering::Relaxed) == 0 {
+            thread::sleep(Duration::from_millis(300));
+        }
+    }
+    let attr = VerifyAttribute {
+        time_limit_ms: 200,
+        repeat: Some(3),
+        ..test_attr(None)
+    };
+    let res = test_judge(&attr, slow_once);
+    assert_eq!(RUNS.load(Ordering::Relaxed), 3);
+    assert_eq!(res.status, JudgeStatus::Accepted);
+    let stats = res.time_stats.unwrap();
+    assert_eq!(stats.runs, 3);
+    assert!(stats.max_ms >= 300 && stats.median_ms < 200);
+
+    let res = test_judge(&attr, |_, _| panic!());
+    assert_eq!(res.status, JudgeStatus::RuntimeError);
+    assert!(res.time_stats.is_none());
+}
+
+#[test]
+fn isolated_panic_entry() {
+    fn panics(_read: &mut dyn Read, _write: &mut dyn Write) {
+        panic!("attempt to divide by zero")
+    }
+    isolated::serve_if_child(panics, &test_attr(None))
+}
+
+#[test]
+fn panic_test() {
+    let res = test_judge(&test_attr(None), |_, _| panic!("attempt to divide by zero"));
+    assert_eq!(res.status, JudgeStatus::RuntimeError);
+    let report = res.panic.unwrap();
+    assert_eq!(report.message, "attempt to divide by zero");
+    assert!(report.location.unwrap().starts_with(file!()));
+
+    let attr = test_attr(Some("verify_core::judge::isolated_panic_entry"));
+    let res = test_judge(&attr, |_, _| ());
+    assert_eq!(res.status, JudgeStatus::RuntimeError);
+    assert_eq!(res.panic.unwrap().message, "attempt to divide by zero");
+}
+
+#[test]
+fn outcome_test() {
+    let ac = || JudgeResult::new("ac".into(), JudgeStatus::Accepted);
+    let missing = || JudgeResult::new("missing".into(), JudgeStatus::MissingFiles);
+    assert_eq!(
+        VerifyResult::new(vec![], 0).outcome(),
+        VerifyOutcome::NoTestCases
+    );
+    assert_eq!(
+        VerifyResult::new(vec![], 3).outcome(),
+        VerifyOutcome::Skipped
+    );
+    assert_eq!(
+        VerifyResult::new(vec![ac()], 3).outcome(),
+        VerifyOutcome::Passed
+    );
+    assert_eq!(
+        VerifyResult::new(vec![ac(), missing()], 2).outcome(),
+        VerifyOutcome::Failed
+    );
+    assert!(!VerifyResult::new(vec![], 0).success());
+
+    let dir = tempfile::tempdir().unwrap();
+    let (in_path, out_path) = (dir.path().join("01.in"), dir.path().join("01.out"));
+    std::fs::write(&in_path, "1\n").unwrap();
+    let res = read_case("01", &in_path, &out_path).unwrap_err();
+    assert_eq!(res.status, JudgeStatus::MissingFiles);
+    assert_eq!(
+        res.message,
+        Some(format!("not found: {}", out_path.display()))
+    );
+}
+
+#[test]
+fn checker_failure_test() {
+    use crate::checker::{CheckResult, Checker, TokenReader, Verdict};
+
+    struct BrokenChecker;
+    impl Checker for BrokenChecker {
+        fn check(
+            &self,
+            _input: &mut TokenReader,
+            _expect: &mut TokenReader,
+            _actual: &mut TokenReader,
+        ) -> CheckResult {
+            Err(Verdict::fail("expected output is broken"))
+        }
+    }
+    let attr = VerifyAttribute {
+        checker: Some(&BrokenChecker),
+        ..test_attr(None)
+    };
+    let res = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_case("test".into(), &[], &[], &attr, |_, _| ()));
+    assert_eq!(res.status, JudgeStatus::InternalError);
+    assert_eq!(res.message.as_deref(), Some("expected output is broken"));
+}
+
+#[test]
+fn solver_assertion_test() {
+    use crate::checker::{CheckResult, Checker, TokenReader};
+
+    /// 1以上の整数なら何でも正解とする
+    struct PositiveChecker;
+    impl Checker for PositiveChecker {
+        fn check(
+            &self,
+            _input: &mut TokenReader,
+            _expect: &mut TokenReader,
+            actual: &mut TokenReader,
+        ) -> CheckResult {
+            actual.read_in_range(1, u64::MAX)?;
+            actual.expect_eof()
+        }
+    }
+    struct AnyPositive;
+    impl Solver for AnyPositive {
+        const PROBLEM_ID: &'static str = "";
+        const CHECKER: Option<&'static dyn Checker> = Some(&PositiveChecker);
+        fn solve(_read: impl Read, mut write: impl Write) {
+            writeln!(write, "2").ok();
+        }
+    }
+    StaticAssertion::equals::<AnyPositive>("", "1\n");
+    AnyPositive::assert("", "1\n");
+    assert_solver::<AnyPositive>("", "1\n");
+    let assert = solver_assertion::<AnyPositive>(Cow::Borrowed(""), Cow::Borrowed("1\n"));
+    assert!(assert.assert("0\n").unwrap().is_err());
+}
+
+#[test]
+fn left_running_test() {
+    fn sleeps(_read: &mut dyn Read, _write: &mut dyn Write) {
+        thread::sleep(Duration::from_millis(1500));
+    }
+    let attr = VerifyAttribute {
+        time_limit_ms: 100,
+        ..test_attr(None)
+    };
+    let res = test_judge(&attr, sleeps);
+    assert_eq!(res.status, JudgeStatus::TimeLimitExceeded);
+    assert!(res
+        .message
+        .unwrap()
+        .starts_with("solver thread is left running"));
 }
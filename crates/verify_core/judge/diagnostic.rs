@@ -0,0 +1,219 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// 解答の出力が誤っている理由
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// 想定解の出力と最初に異なる箇所
+    Mismatch(Box<Mismatch>),
+    /// チェッカーやインタラクターのメッセージ
+    Message(String),
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Mismatch(mismatch) => write!(f, "{mismatch}"),
+            Self::Message(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// 比較の単位 `token`または`line`
+    pub unit: &'static str,
+    /// 最初に異なる単位の番号(0-indexed) 順序を問わない比較では整列した後の番号
+    pub index: usize,
+    /// 解答の出力での位置(1-indexed) 解答の出力が先に終わった場合は末尾
+    pub line: usize,
+    pub column: usize,
+    /// 異なる単位 出力が先に終わっていればNone
+    pub expect: Option<String>,
+    pub actual: Option<String>,
+    /// 解答の出力の該当箇所の前後
+    pub context: String,
+    pub expect_len: usize,
+    pub actual_len: usize,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let show = |unit: &Option<String>| {
+            unit.as_ref()
+                .map_or("EOF".to_string(), |u| format!("{u:?}"))
+        };
+        write!(
+            f,
+            "{} {} (line {}, column {}): expected {}, found {}",
+            self.unit,
+            self.index,
+            self.line,
+            self.column,
+            show(&self.expect),
+            show(&self.actual)
+        )?;
+        if !self.context.is_empty() {
+            write!(f, " near {:?}", self.context)?;
+        }
+        if self.expect_len != self.actual_len {
+            write!(
+                f,
+                ", {} count: expected {}, found {}",
+                self.unit, self.expect_len, self.actual_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 診断に載せる1単位の最大文字数
+const MAX_UNIT_CHARS: usize = 64;
+/// 前後何単位を文脈として載せるか
+const CONTEXT_UNITS: usize = 2;
+
+/// 出力を比較する単位と、その出力中の位置(1-indexed)
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Unit<'a> {
+    pub text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// 空白区切りのトークン
+pub(super) fn tokens(s: &str) -> Vec<Unit<'_>> {
+    let mut units = Vec::new();
+    for (i, line) in s.split('\n').enumerate() {
+        let (mut offset, mut column) = (0, 1);
+        for token in line.split_ascii_whitespace() {
+            let start = token.as_ptr() as usize - line.as_ptr() as usize;
+            column += line[offset..start].chars().count();
+            offset = start;
+            units.push(Unit {
+                text: token,
+                line: i + 1,
+                column,
+            });
+        }
+    }
+    units
+}
+
+/// 行末の空白と末尾の空行を除いた行
+pub(super) fn lines(s: &str) -> Vec<Unit<'_>> {
+    let mut units = s
+        .lines()
+        .map(str::trim_end)
+        .enumerate()
+        .map(|(i, text)| Unit {
+            text,
+            line: i + 1,
+            column: 1,
+        })
+        .collect::<Vec<_>>();
+    while units.last().is_some_and(|u| u.text.is_empty()) {
+        units.pop();
+    }
+    units
+}
+
+/// 改行文字を含めた行 全ての行が一致すれば出力全体が一致する
+pub(super) fn raw_lines(s: &str) -> Vec<Unit<'_>> {
+    s.split_inclusive('\n')
+        .enumerate()
+        .map(|(i, text)| Unit {
+            text,
+            line: i + 1,
+            column: 1,
+        })
+        .collect()
+}
+
+pub(super) fn sorted(mut units: Vec<Unit>) -> Vec<Unit> {
+    units.sort_unstable_by_key(|u| u.text);
+    units
+}
+
+/// 単位ごとに比較し、異なる箇所があれば最初のものを返す
+pub(super) fn compare(
+    unit: &'static str,
+    expect: &[Unit],
+    actual: &[Unit],
+    eq: impl Fn(&str, &str) -> bool,
+) -> Option<Mismatch> {
+    let index = match expect
+        .iter()
+        .zip(actual)
+        .position(|(ex, ac)| !eq(ex.text, ac.text))
+    {
+        Some(index) => index,
+        None if expect.len() == actual.len() => return None,
+        None => expect.len().min(actual.len()),
+    };
+    let (line, column) = match (expect.get(index), actual.get(index)) {
+        (Some(ex), Some(ac)) => {
+            // 単位の中で最初に異なる文字を指す
+            let common = ex
+                .text
+                .chars()
+                .zip(ac.text.chars())
+                .take_while(|(e, a)| e == a)
+                .count();
+            (ac.line, ac.column + common)
+        }
+        (_, Some(ac)) => (ac.line, ac.column),
+        (_, None) => actual
+            .last()
+            .map_or((1, 1), |ac| (ac.line, ac.column + ac.text.chars().count())),
+    };
+    let separator = if unit == "token" { " " } else { " ↵ " };
+    let context = actual
+        [index.saturating_sub(CONTEXT_UNITS)..(index + CONTEXT_UNITS + 1).min(actual.len())]
+        .iter()
+        .map(|u| truncate(u.text.trim_end_matches('\n')))
+        .collect::<Vec<_>>()
+        .join(separator);
+    Some(Mismatch {
+        unit,
+        index,
+        line,
+        column,
+        expect: expect.get(index).map(|u| truncate(u.text)),
+        actual: actual.get(index).map(|u| truncate(u.text)),
+        context,
+        expect_len: expect.len(),
+        actual_len: actual.len(),
+    })
+}
+
+fn truncate(s: &str) -> String {
+    match s.char_indices().nth(MAX_UNIT_CHARS) {
+        Some((i, _)) => format!("{}...", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+#[test]
+fn mismatch_test() {
+    let eq = |ex: &str, ac: &str| ex == ac;
+    let expect = "1 2 3\n4 5 6\n";
+    let mismatch = compare("token", &tokens(expect), &tokens("1 2 3\n4  57 6\n"), eq).unwrap();
+    assert_eq!(mismatch.index, 4);
+    assert_eq!((mismatch.line, mismatch.column), (2, 5));
+    assert_eq!(mismatch.expect.as_deref(), Some("5"));
+    assert_eq!(mismatch.actual.as_deref(), Some("57"));
+    assert_eq!(mismatch.context, "3 4 57 6");
+
+    let mismatch = compare("token", &tokens(expect), &tokens("1 2 3\n4"), eq).unwrap();
+    assert_eq!(mismatch.index, 4);
+    assert_eq!((mismatch.line, mismatch.column), (2, 2));
+    assert_eq!(mismatch.actual, None);
+    assert_eq!((mismatch.expect_len, mismatch.actual_len), (6, 4));
+    assert_eq!(
+        mismatch.to_string(),
+        "token 4 (line 2, column 2): expected \"5\", found EOF near \"3 4\", token count: expected 6, found 4"
+    );
+
+    let mismatch = compare("line", &lines(expect), &lines("1 2 3\n4 5 7\n"), eq).unwrap();
+    assert_eq!((mismatch.line, mismatch.column), (2, 5));
+    assert!(compare("line", &raw_lines(expect), &raw_lines(expect), eq).is_none());
+}
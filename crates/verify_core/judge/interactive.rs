@@ -0,0 +1,453 @@
+use super::{
+    diagnostic::Diagnostic, hard_time_limit, repeat, spawn_solver, wait_solver, within_time_limit,
+    Execution, JudgeResult, JudgeStatus, Termination, TIME_LIMIT_GRACE_MILLIS,
+};
+use crate::{
+    attribute::VerifyAttribute,
+    checker::{CheckResult, Source, TokenReader, Verdict},
+    interactor::Interactor,
+    SolveFunc,
+};
+use anyhow::Context;
+use std::{
+    io::{self, BufReader, Read, Write},
+    panic,
+    path::Path,
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, time};
+
+/// 解答の対話相手
+#[derive(Clone, Copy, Debug)]
+pub enum Interaction<'a> {
+    /// Rustで実装されたインタラクター
+    Native(&'static dyn Interactor),
+    /// testlib形式のインタラクターのバイナリ
+    /// `interactor <input> <output> <answer>`として起動し、標準入出力で解答と対話する
+    Binary(&'a Path),
+}
+
+/// 解答とインタラクターを対話させて1ケース分を判定する
+/// 実行時間制限は解答側にのみ適用する 解答は隔離実行の指定によらずプロセス内のスレッドで実行する
+pub async fn verify_inner(
+    name: String,
+    input: &[u8],
+    expect: &[u8],
+    interaction: Interaction<'_>,
+    attr: &VerifyAttribute,
+    f: SolveFunc,
+) -> JudgeResult {
+    repeat(attr, || {
+        verify_once(name.clone(), input, expect, interaction, attr, f)
+    })
+    .await
+}
+
+async fn verify_once(
+    name: String,
+    input: &[u8],
+    expect: &[u8],
+    interaction: Interaction<'_>,
+    attr: &VerifyAttribute,
+    f: SolveFunc,
+) -> (JudgeResult, Option<Vec<u8>>) {
+    let mut ret = JudgeResult::new(name, JudgeStatus::InternalError);
+    let limit = hard_time_limit(attr);
+    let result = match interaction {
+        Interaction::Native(interactor) => {
+            execute_native(f, input, expect, interactor, attr, limit).await
+        }
+        Interaction::Binary(path) => execute_binary(f, input, expect, path, attr, limit).await,
+    };
+    let (execution, verdict) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{:?}", e);
+            ret.message = Some(e.to_string());
+            return (ret, None);
+        }
+    };
+    ret.exec_time_ms = execution.elapsed.as_millis() as u64;
+    ret.cpu_time_ms = execution.cpu_time.map(|t| t.as_millis() as u64);
+    match (execution.termination, verdict) {
+        (Termination::Timeout, _) => ret.record_abandoned(),
+        (Termination::OutputLimitExceeded, _) => ret.status = JudgeStatus::OutputLimitExceeded,
+        (Termination::MemoryLimitExceeded, _) => ret.status = JudgeStatus::MemoryLimitExceeded,
+        // インタラクターが先に打ち切ったことで解答が異常終了することがあるため、WAを優先する
+        (_, Some(Err(Verdict::WrongAnswer(message)))) => {
+            println!("{}: {message}", ret.name);
+            ret.status = JudgeStatus::WrongAnswer;
+            ret.diagnostic = Some(Diagnostic::Message(message));
+        }
+        (_, Some(Err(Verdict::Fail(message)))) => ret.message = Some(message),
+        (Termination::Panicked(report), _) => ret.record_panic(report),
+        (Termination::Aborted(reason), _) => {
+            ret.status = JudgeStatus::RuntimeError;
+            ret.message = Some(reason);
+        }
+        (_, None) => ret.message = Some("interactor did not finish".to_string()),
+        (Termination::Exited(_), Some(Ok(()))) => {
+            if within_time_limit(&ret, attr) {
+                ret.status = JudgeStatus::Accepted
+            } else {
+                ret.status = JudgeStatus::TimeLimitExceeded
+            }
+        }
+    }
+    (ret, None)
+}
+
+/// Rustのインタラクターを別スレッドで実行し、解答とパイプでつなぐ
+async fn execute_native(
+    f: SolveFunc,
+    input: &[u8],
+    expect: &[u8],
+    interactor: &'static dyn Interactor,
+    attr: &VerifyAttribute,
+    limit: Duration,
+) -> anyhow::Result<(Execution, Option<CheckResult>)> {
+    let (mut to_solver, solver_in) = pipe();
+    let (solver_out, from_solver) = pipe();
+    let input = String::from_utf8_lossy(input).into_owned();
+    let expect = String::from_utf8_lossy(expect).into_owned();
+    let (tx, interacted) = oneshot::channel();
+    thread::Builder::new()
+        .name("interactor".to_string())
+        .spawn(move || {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                interactor.interact(
+                    &mut TokenReader::new(&input, Source::Input),
+                    &mut TokenReader::new(&expect, Source::Expect),
+                    &mut BufReader::new(from_solver),
+                    &mut to_solver,
+                )
+            }));
+            // 解答の入力を閉じてから結果を返す
+            drop(to_solver);
+            tx.send(result.unwrap_or_else(|_| Err(Verdict::fail("interactor panicked"))))
+                .ok();
+        })?;
+    let run = spawn_solver(
+        f,
+        solver_in,
+        solver_out,
+        attr.stack_size,
+        attr.output_limit_bytes,
+    )?;
+    let execution = wait_solver(run, limit, |_| Vec::new()).await?;
+    let verdict = match execution.termination {
+        // 解答が止まっていないため、インタラクターも終わらないことがある
+        Termination::Timeout => None,
+        _ => time::timeout(Duration::from_millis(TIME_LIMIT_GRACE_MILLIS), interacted)
+            .await
+            .ok()
+            .and_then(Result::ok),
+    };
+    Ok((execution, verdict))
+}
+
+/// インタラクターのバイナリを子プロセスとして起動し、解答とその標準入出力をつなぐ
+async fn execute_binary(
+    f: SolveFunc,
+    input: &[u8],
+    expect: &[u8],
+    path: &Path,
+    attr: &VerifyAttribute,
+    limit: Duration,
+) -> anyhow::Result<(Execution, Option<CheckResult>)> {
+    let input_file = crate::save_temp_file(input)?;
+    let expect_file = crate::save_temp_file(expect)?;
+    let output_file = tempfile::NamedTempFile::new()?;
+    let mut child = Command::new(path)
+        .args([input_file.path(), output_file.path(), expect_file.path()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not start interactor {}", path.display()))?;
+    let to_solver = child
+        .stdout
+        .take()
+        .context("could not open stdout of interactor")?;
+    let from_solver = child
+        .stdin
+        .take()
+        .context("could not open stdin of interactor")?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("could not open stderr of interactor")?;
+    let stderr = thread::spawn(move || {
+        let mut buf = String::new();
+        stderr.read_to_string(&mut buf).ok();
+        buf
+    });
+    let run = spawn_solver(
+        f,
+        to_solver,
+        from_solver,
+        attr.stack_size,
+        attr.output_limit_bytes,
+    )?;
+    // 解答が終了すると書き込み先が閉じられ、インタラクターの入力がEOFになる
+    let execution = wait_solver(run, limit, |_| Vec::new()).await?;
+    let deadline = match execution.termination {
+        Termination::Timeout => Instant::now(),
+        _ => Instant::now() + Duration::from_millis(TIME_LIMIT_GRACE_MILLIS),
+    };
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            // インタラクターが止まれば、入力を待っている解答もEOFを受け取って終了する
+            child.kill().ok();
+            child.wait().ok();
+            break None;
+        }
+        time::sleep(Duration::from_millis(10)).await;
+    };
+    let verdict = status.map(|status| {
+        let message = stderr.join().unwrap_or_default().trim().to_string();
+        // testlibの終了コード 0: AC, 1: WA, 2: PE
+        match status.code() {
+            Some(0) => Ok(()),
+            Some(1 | 2) => Err(Verdict::WrongAnswer(message)),
+            _ if message.is_empty() => Err(Verdict::Fail(format!("interactor {status}"))),
+            _ => Err(Verdict::Fail(message)),
+        }
+    });
+    Ok((execution, verdict))
+}
+
+/// スレッド間で解答とインタラクターをつなぐパイプを作る
+/// 書き込み側が閉じられると、読み込み側はEOFになる
+fn pipe() -> (PipeWriter, PipeReader) {
+    let (tx, rx) = mpsc::channel();
+    (
+        PipeWriter(tx),
+        PipeReader {
+            rx,
+            buf: Vec::new(),
+            pos: 0,
+        },
+    )
+}
+
+struct PipeWriter(mpsc::Sender<Vec<u8>>);
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "pipe is closed"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct PipeReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.buf.len() {
+            match self.rx.recv() {
+                Ok(buf) => {
+                    self.buf = buf;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let len = out.len().min(self.buf.len() - self.pos);
+        out[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+fn test_interact(interaction: Interaction, input: &str, f: SolveFunc) -> JudgeResult {
+    let attr = VerifyAttribute {
+        time_limit_ms: 500,
+        ..super::test_attr(None)
+    };
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_inner(
+            "test".into(),
+            input.as_bytes(),
+            &[],
+            interaction,
+            &attr,
+            f,
+        ))
+}
+
+/// 1以上n以下の秘密の数を、大小を答えながら当てさせる
+#[cfg(test)]
+struct GuessInteractor;
+#[cfg(test)]
+impl Interactor for GuessInteractor {
+    fn interact(
+        &self,
+        input: &mut TokenReader,
+        _expect: &mut TokenReader,
+        from_solver: &mut dyn std::io::BufRead,
+        to_solver: &mut dyn Write,
+    ) -> CheckResult {
+        use crate::{checker::ensure, interactor::read_from_solver};
+        let n = input.read::<u32>()?;
+        let secret = input.read::<u32>()?;
+        writeln!(to_solver, "{n}").map_err(Verdict::fail)?;
+        for _ in 0..32 {
+            let guess = read_from_solver::<u32>(from_solver)?;
+            let reply = match guess.cmp(&secret) {
+                std::cmp::Ordering::Less => "<",
+                std::cmp::Ordering::Equal => return Ok(()),
+                std::cmp::Ordering::Greater => ">",
+            };
+            // 解答が終了していれば書き込めないが、次の読み込みでWAになる
+            writeln!(to_solver, "{reply}").ok();
+        }
+        ensure(false, "too many queries")
+    }
+}
+
+#[cfg(test)]
+fn guess(read: &mut dyn Read, write: &mut dyn Write) {
+    let mut read = BufReader::new(read);
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut read, &mut line).unwrap();
+    let (mut lo, mut hi) = (1, line.trim().parse::<u32>().unwrap());
+    loop {
+        let mid = (lo + hi) / 2;
+        writeln!(write, "{mid}").unwrap();
+        line.clear();
+        if std::io::BufRead::read_line(&mut read, &mut line).unwrap() == 0 {
+            return;
+        }
+        match line.trim() {
+            "<" => lo = mid + 1,
+            _ => hi = mid - 1,
+        }
+    }
+}
+
+#[test]
+fn interactive_test() {
+    let native = Interaction::Native(&GuessInteractor);
+    let res = test_interact(native, "100 37", guess);
+    assert_eq!(res.status, JudgeStatus::Accepted);
+
+    fn guesses_one(_read: &mut dyn Read, write: &mut dyn Write) {
+        writeln!(write, "1").unwrap();
+    }
+    let res = test_interact(native, "100 37", guesses_one);
+    assert_eq!(res.status, JudgeStatus::WrongAnswer);
+
+    fn waits_forever(read: &mut dyn Read, _write: &mut dyn Write) {
+        read.read_to_end(&mut Vec::new()).ok();
+    }
+    let res = test_interact(native, "100 37", waits_forever);
+    assert_eq!(res.status, JudgeStatus::TimeLimitExceeded);
+
+    let res = test_interact(native, "broken", guess);
+    assert_eq!(res.status, JudgeStatus::InternalError);
+}
+
+#[cfg(unix)]
+#[test]
+fn interactive_binary_test() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // 入力ファイルの数を渡し、2倍にして返せばAC
+    let mut interactor = tempfile::NamedTempFile::new().unwrap();
+    writeln!(
+        interactor,
+        "#!/bin/sh\nread n < \"$1\"\necho \"$n\"\nread m\n[ \"$m\" = \"$((n * 2))\" ] || exit 1"
+    )
+    .unwrap();
+    let interactor = interactor.into_temp_path();
+    std::fs::set_permissions(&interactor, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    fn doubles(read: &mut dyn Read, write: &mut dyn Write) {
+        let mut line = String::new();
+        std::io::BufRead::read_line(&mut BufReader::new(read), &mut line).unwrap();
+        writeln!(write, "{}", line.trim().parse::<u32>().unwrap() * 2).unwrap();
+    }
+    let res = test_interact(Interaction::Binary(&interactor), "21", doubles);
+    assert_eq!(res.status, JudgeStatus::Accepted);
+
+    fn echoes(read: &mut dyn Read, write: &mut dyn Write) {
+        std::io::copy(&mut read.take(3), write).unwrap();
+    }
+    let res = test_interact(Interaction::Binary(&interactor), "21", echoes);
+    assert_eq!(res.status, JudgeStatus::WrongAnswer);
+}
+
+#[test]
+fn interactive_isolated_test() {
+    use crate::{service::library_checker::LibraryChecker, Solver, Verifiable};
+
+    struct Guess;
+    impl Solver for Guess {
+        const PROBLEM_ID: &'static str = "";
+        const INTERACTOR: Option<&'static dyn Interactor> = Some(&GuessInteractor);
+        fn solve(mut read: impl Read, mut write: impl Write) {
+            guess(&mut read, &mut write)
+        }
+    }
+    impl Verifiable for Guess {
+        type SERVICE = LibraryChecker;
+        const ENTRY_POINT: Option<&'static str> = Some("verify_core::judge::verify_Guess");
+    }
+    let attr = Guess::attribute();
+    let res = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_inner(
+            "test".into(),
+            b"100 37",
+            &[],
+            Interaction::Native(attr.interactor.unwrap()),
+            &attr,
+            Guess::verify_inner,
+        ));
+    assert_eq!(res.status, JudgeStatus::Accepted);
+
+    // インタラクターのバイナリが見つかった問題は、隔離実行の指定があっても対話させる
+    let attr = VerifyAttribute {
+        time_limit_ms: 500,
+        ..super::test_attr(Some("verify_core::judge::verify_Guess"))
+    };
+    let res = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(verify_inner(
+            "test".into(),
+            b"100 37",
+            &[],
+            Interaction::Native(&GuessInteractor),
+            &attr,
+            guess,
+        ));
+    assert_eq!(res.status, JudgeStatus::Accepted);
+}
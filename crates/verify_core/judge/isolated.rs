@@ -0,0 +1,281 @@
+use super::{
+    panic_report, Execution, LimitedWriter, OutputLimitExceeded, PanicReport, Termination,
+};
+use crate::{attribute::VerifyAttribute, SolveFunc};
+use anyhow::{anyhow, Context};
+use std::{
+    env,
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+    process::{self, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, time};
+
+/// 子プロセスに解答の出力先を伝える環境変数
+/// テストハーネスが標準出力を使うため、出力はファイル経由で受け渡す
+const CHILD_OUTPUT_ENV: &str = "RUST_JUDGE_CHILD_OUTPUT";
+/// 子プロセスで解答がpanicした場合の終了コード
+const PANIC_EXIT_CODE: i32 = 101;
+/// 子プロセスで出力が上限を超えた場合の終了コード
+const OUTPUT_LIMIT_EXIT_CODE: i32 = 102;
+/// 子プロセスで解答のスレッドを起動できなかった場合の終了コード
+const SPAWN_FAILURE_EXIT_CODE: i32 = 103;
+/// 暴走した解答がCIのメモリを使い果たさないよう、メモリ制限の何倍でアロケーションを失敗させるか
+const MEMORY_HARD_LIMIT_RATIO: u64 = 2;
+
+/// テストバイナリを子プロセスとして再実行し、1ケース分の解答を実行する
+#[cfg(unix)]
+pub(super) async fn execute(
+    input: &[u8],
+    attr: &VerifyAttribute,
+    limit: Duration,
+) -> anyhow::Result<Execution> {
+    use std::os::unix::process::CommandExt;
+
+    let Some(entry_point) = &attr.entry_point else {
+        return Err(anyhow!(
+            "entry point for isolated execution is not specified"
+        ));
+    };
+    // 出力のファイルは子プロセスが解答した場合にのみ作られる
+    let output_dir = tempfile::tempdir()?;
+    let output = output_dir.path().join("output");
+    // スタックの領域も確保した時点で制限に数えられるため、その分を加える
+    let hard_limit =
+        attr.memory_limit_mb * MEMORY_HARD_LIMIT_RATIO * 1024 * 1024 + attr.stack_size as u64;
+    let mut command = Command::new(env::current_exe()?);
+    command
+        .args([
+            test_name(entry_point),
+            "--exact",
+            "--include-ignored",
+            "--test-threads=1",
+            "--quiet",
+        ])
+        .env(CHILD_OUTPUT_ENV, &output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    // Safety: setrlimitはasync-signal-safeであり、fork後に呼び出してよい
+    unsafe {
+        command.pre_exec(move || limit_memory(hard_limit));
+    }
+    let mut child = command.spawn()?;
+    let now = Instant::now();
+    let pid = child.id();
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("could not open stdin of child process")?;
+    let input = input.to_vec();
+    thread::spawn(move || {
+        // 入力を読み切らずに終了した場合の書き込みエラーは無視する
+        stdin.write_all(&input).ok();
+    });
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("could not open stderr of child process")?;
+    let stderr = thread::spawn(move || {
+        let mut buf = Vec::new();
+        stderr.read_to_end(&mut buf).ok();
+        buf
+    });
+    let (tx, mut rx) = oneshot::channel();
+    // 終了を待つだけで回収はしないため、回収するまでpidが再利用されず安全にkillできる
+    thread::spawn(move || tx.send(wait_exit(pid)));
+
+    let timeout = match time::timeout(limit, &mut rx).await {
+        Ok(exited) => {
+            exited??;
+            false
+        }
+        Err(_) => {
+            kill(pid);
+            rx.await??;
+            true
+        }
+    };
+    let elapsed = now.elapsed();
+    let (status, peak_memory_kb, cpu_time) = reap(pid)?;
+    let stderr = stderr.join().unwrap_or_default();
+    io::stderr().write_all(&stderr).ok();
+
+    let termination = if timeout {
+        Termination::Timeout
+    } else if status.success() {
+        // テストが見つからなくてもlibtestは正常終了するため、出力のファイルで解答したことを確かめる
+        if !output.exists() {
+            return Err(anyhow!(
+                "entry point {entry_point} did not run the solver, check that the test exists and the verify feature is enabled"
+            ));
+        }
+        Termination::Exited(crate::read_file(&output)?)
+    } else if status.code() == Some(SPAWN_FAILURE_EXIT_CODE) {
+        return Err(anyhow!(
+            "could not spawn solver thread with stack size {} in child process: {}",
+            attr.stack_size,
+            String::from_utf8_lossy(&stderr).trim()
+        ));
+    } else if status.code() == Some(OUTPUT_LIMIT_EXIT_CODE) {
+        Termination::OutputLimitExceeded
+    } else if let Some(report) = (status.code() == Some(PANIC_EXIT_CODE))
+        .then(|| read_panic_report(&output))
+        .flatten()
+    {
+        Termination::Panicked(report)
+    } else if String::from_utf8_lossy(&stderr).contains("memory allocation of") {
+        Termination::MemoryLimitExceeded
+    } else if String::from_utf8_lossy(&stderr).contains("has overflowed its stack") {
+        Termination::Aborted(format!("stack overflow ({status})"))
+    } else {
+        Termination::Aborted(describe(status))
+    };
+    Ok(Execution {
+        termination,
+        elapsed: if timeout { limit } else { elapsed },
+        cpu_time: Some(cpu_time),
+        peak_memory_kb: Some(peak_memory_kb),
+    })
+}
+
+#[cfg(not(unix))]
+pub(super) async fn execute(
+    _input: &[u8],
+    _attr: &VerifyAttribute,
+    _limit: Duration,
+) -> anyhow::Result<Execution> {
+    Err(anyhow!("isolated execution is only supported on unix"))
+}
+
+/// 子プロセスとして起動されていれば、標準入力を解答に与えて出力をファイルに書き出し、終了する
+pub fn serve_if_child(f: SolveFunc, attr: &VerifyAttribute) {
+    let Some(path) = env::var_os(CHILD_OUTPUT_ENV) else {
+        return;
+    };
+    let mut input = Vec::new();
+    if io::stdin().read_to_end(&mut input).is_err() {
+        process::exit(1)
+    }
+    let output_limit = attr.output_limit_bytes;
+    let solver = thread::Builder::new()
+        .name("solver".to_string())
+        .stack_size(attr.stack_size)
+        .spawn(move || {
+            panic_report::catch(|| {
+                let mut output = LimitedWriter::new(Vec::new(), output_limit);
+                f(&mut input.as_slice(), &mut output);
+                output.inner
+            })
+        });
+    let solver = match solver {
+        Ok(solver) => solver,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(SPAWN_FAILURE_EXIT_CODE)
+        }
+    };
+    let Ok(result) = solver.join() else {
+        process::exit(1)
+    };
+    match result {
+        Ok(output) => match File::create(path).and_then(|mut file| file.write_all(&output)) {
+            Ok(()) => process::exit(0),
+            Err(_) => process::exit(1),
+        },
+        Err(payload) if payload.is::<OutputLimitExceeded>() => {
+            process::exit(OUTPUT_LIMIT_EXIT_CODE)
+        }
+        Err(payload) => {
+            // panicの情報は出力の代わりにファイルで親プロセスに渡す
+            if let Ok(report) = payload.downcast::<PanicReport>() {
+                if let Ok(file) = File::create(path) {
+                    serde_json::to_writer(file, &report).ok();
+                }
+            }
+            process::exit(PANIC_EXIT_CODE)
+        }
+    }
+}
+
+/// 子プロセスが書き出したpanicの情報を読む
+fn read_panic_report(path: &Path) -> Option<PanicReport> {
+    serde_json::from_reader(File::open(path).ok()?).ok()
+}
+
+/// `module_path!()`から作ったエントリポイントをlibtestのテスト名に変換する
+fn test_name(entry_point: &str) -> &str {
+    entry_point
+        .split_once("::")
+        .map_or(entry_point, |(_crate_name, name)| name)
+}
+
+fn describe(status: ExitStatus) -> String {
+    match status.code() {
+        Some(PANIC_EXIT_CODE) => "panicked".to_string(),
+        _ => status.to_string(),
+    }
+}
+
+#[cfg(unix)]
+fn limit_memory(bytes: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    match unsafe { libc::setrlimit(libc::RLIMIT_DATA, &limit) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// 子プロセスが終了するまで待つ 終了した子プロセスはゾンビのまま残す
+#[cfg(unix)]
+fn wait_exit(pid: u32) -> io::Result<()> {
+    let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+    loop {
+        let options = libc::WEXITED | libc::WNOWAIT;
+        match unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, options) } {
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// 終了した子プロセスを回収し、終了ステータスと最大使用メモリ(KB)とCPU時間を返す
+#[cfg(unix)]
+fn reap(pid: u32) -> io::Result<(ExitStatus, u64, Duration)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+    loop {
+        match unsafe { libc::wait4(pid as libc::pid_t, &mut status, 0, &mut usage) } {
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            _ => break,
+        }
+    }
+    // macOSではバイト単位で返る
+    let peak_memory_kb = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64 / 1024
+    } else {
+        usage.ru_maxrss as u64
+    };
+    let cpu_time = [usage.ru_utime, usage.ru_stime]
+        .iter()
+        .map(|t| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000))
+        .sum();
+    Ok((ExitStatus::from_raw(status), peak_memory_kb, cpu_time))
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) {
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
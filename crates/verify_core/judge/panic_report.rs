@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    cell::{Cell, RefCell},
+    fmt::{Display, Formatter, Result},
+    panic,
+    sync::Once,
+    thread,
+};
+
+/// 解答がpanicした際の情報
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicReport {
+    pub message: String,
+    /// `ファイル:行:列`
+    pub location: Option<String>,
+    /// `RUST_BACKTRACE`が有効な場合のみ記録する
+    pub backtrace: Option<String>,
+}
+
+impl Display for PanicReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {location}: {}", self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+thread_local! {
+    /// このスレッドのpanicを記録するか
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    static CAPTURED: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+/// 解答のスレッドでは既定の出力の代わりにpanicの情報を記録するフックを設定する
+/// 他のスレッドのpanicは元のフックに渡す
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CAPTURING.with(Cell::get) {
+                return default(info);
+            }
+            let payload = info.payload();
+            let message = match (
+                payload.downcast_ref::<&str>(),
+                payload.downcast_ref::<String>(),
+            ) {
+                (Some(s), _) => s.to_string(),
+                (_, Some(s)) => s.clone(),
+                (None, None) => "Box<dyn Any>".to_string(),
+            };
+            let backtrace = Backtrace::capture();
+            let report = PanicReport {
+                message,
+                location: info.location().map(ToString::to_string),
+                backtrace: (backtrace.status() == BacktraceStatus::Captured)
+                    .then(|| backtrace.to_string()),
+            };
+            CAPTURED.with(|captured| *captured.borrow_mut() = Some(report));
+        }));
+    });
+}
+
+/// `f`を実行し、panicした場合は記録した`PanicReport`をペイロードとして返す
+/// `resume_unwind`による巻き戻しはフックを通らないため、元のペイロードのまま返す
+pub(crate) fn catch<R>(f: impl FnOnce() -> R) -> thread::Result<R> {
+    install_hook();
+    CAPTURING.with(|capturing| capturing.set(true));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    CAPTURING.with(|capturing| capturing.set(false));
+    let captured = CAPTURED.with(|captured| captured.borrow_mut().take());
+    result.map_err(|payload| match captured {
+        Some(report) => Box::new(report),
+        None => payload,
+    })
+}
+
+#[test]
+fn catch_test() {
+    let payload = catch(|| panic!("index {} out of range", 3)).unwrap_err();
+    let report = payload.downcast::<PanicReport>().unwrap();
+    assert_eq!(report.message, "index 3 out of range");
+    assert!(report
+        .to_string()
+        .starts_with(&format!("panicked at {}:", file!())));
+    assert_eq!(catch(|| 1).unwrap(), 1);
+}
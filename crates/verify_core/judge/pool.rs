@@ -0,0 +1,95 @@
+use super::{JudgeResult, JudgeStatus};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+use tokio::runtime::{self, Runtime};
+
+/// `jobs`個のワーカーでケースを並列に判定し、`cases`と同じ順序で結果を返す
+/// ワーカーごとにランタイムを1つ作り、担当するケースの判定に使い回す
+/// `fail_fast`の場合、ACでないケースがあればそれ以降のケースは判定せず、結果にも含めない
+pub fn run<T: Sync>(
+    cases: &[T],
+    jobs: usize,
+    fail_fast: bool,
+    judge: impl Fn(&Runtime, &T) -> JudgeResult + Sync,
+) -> Vec<JudgeResult> {
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let results = Mutex::new(vec![None; cases.len()]);
+    let worker = || {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        while !failed.load(Ordering::Relaxed) {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(case) = cases.get(i) else { break };
+            let result = judge(&runtime, case);
+            if fail_fast && result.status != JudgeStatus::Accepted {
+                failed.store(true, Ordering::Relaxed);
+            }
+            results.lock().unwrap()[i] = Some(result);
+        }
+    };
+    let jobs = jobs.clamp(1, cases.len().max(1));
+    if jobs == 1 {
+        worker();
+    } else {
+        thread::scope(|s| {
+            for _ in 0..jobs {
+                s.spawn(worker);
+            }
+        });
+    }
+    let results: Vec<_> = results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    if results.len() < cases.len() {
+        println!(
+            "fail fast: {} of {} cases were not judged",
+            cases.len() - results.len(),
+            cases.len()
+        );
+    }
+    results
+}
+
+#[test]
+fn pool_test() {
+    use std::time::{Duration, Instant};
+
+    let cases: Vec<u64> = (0..8).rev().collect();
+    let judge = |_: &Runtime, case: &u64| {
+        thread::sleep(Duration::from_millis(case * 10));
+        JudgeResult::new(case.to_string(), JudgeStatus::Accepted)
+    };
+    let now = Instant::now();
+    let names = |results: Vec<JudgeResult>| results.into_iter().map(|r| r.name).collect::<Vec<_>>();
+    let expect: Vec<_> = cases.iter().map(u64::to_string).collect();
+    assert_eq!(names(run(&cases, 4, false, judge)), expect);
+    let parallel = now.elapsed();
+    let now = Instant::now();
+    assert_eq!(names(run(&cases, 1, false, judge)), expect);
+    assert!(parallel < now.elapsed());
+}
+
+#[test]
+fn fail_fast_test() {
+    let cases: Vec<u64> = (0..8).collect();
+    let judge = |_: &Runtime, case: &u64| {
+        let status = match case {
+            2 => JudgeStatus::WrongAnswer,
+            _ => JudgeStatus::Accepted,
+        };
+        JudgeResult::new(case.to_string(), status)
+    };
+    assert_eq!(run(&cases, 1, true, judge).len(), 3);
+    assert_eq!(run(&cases, 1, false, judge).len(), 8);
+}
@@ -1,15 +1,22 @@
+pub mod artifact;
 pub mod attribute;
+pub mod calibration;
+pub mod checker;
+pub mod config;
+pub mod doc_sample;
+pub mod interactor;
 pub mod judge;
+pub mod local_cases;
 pub mod service;
+pub mod stress;
 
 use anyhow::Error;
 use attribute::VerifyAttribute;
 use chrono::SecondsFormat;
 use dirs::cache_dir;
-use judge::VerifyResult;
+use judge::{CaseSource, VerifyOutcome, VerifyResult};
 use serde::Deserialize;
 use std::{
-    borrow::Cow,
     env::temp_dir,
     fs::{create_dir_all, File, OpenOptions},
     io::{Read, Write},
@@ -19,11 +26,16 @@ use std::{
 };
 use tempfile::NamedTempFile;
 
-use crate::judge::{Assertion, StaticAssertion};
+use crate::{
+    checker::Checker,
+    config::{TimeBasis, VerifyConfig},
+    interactor::Interactor,
+    judge::CompareMode,
+};
 
 const APP_NAME: &str = "rust_judge";
 
-type SolveFunc = fn(&[u8], &mut Vec<u8>);
+type SolveFunc = fn(&mut dyn Read, &mut dyn Write);
 
 pub trait Service {
     fn verify(attr: VerifyAttribute, f: SolveFunc) -> anyhow::Result<VerifyResult>;
@@ -41,21 +53,36 @@ pub trait Solver {
     const PROBLEM_ID: &'static str;
     const EPSILON: Option<f64> = None;
     const TIME_LIMIT_MILLIS: u64 = 10000;
+    /// 使用メモリは隔離実行した場合のみ計測される
+    const MEMORY_LIMIT_MB: u64 = 1024;
+    /// 解答を実行するスレッドのスタックサイズ(バイト)
+    /// スタックオーバーフローをREとして報告するには隔離実行が必要
+    const STACK_SIZE: usize = 100 * 1024 * 1024;
+    /// 出力がこのバイト数を超えた時点で打ち切り、OLEとする
+    const OUTPUT_LIMIT_BYTES: usize = 256 * 1024 * 1024;
+    /// 出力の比較方法 Noneの場合はサービスごとの既定の方法で判定する
+    const COMPARE_MODE: Option<CompareMode> = None;
+    /// 指定されていれば出力の判定に使う
+    const CHECKER: Option<&'static dyn Checker> = None;
+    /// 各ケースを繰り返し実行する回数 Noneの場合は設定に従う
+    const REPEAT: Option<usize> = None;
+    /// 指定されていれば、出力を比較する代わりに解答と対話させて判定する
+    const INTERACTOR: Option<&'static dyn Interactor> = None;
+    /// ケースごとにテストバイナリを子プロセスとして再実行して解答する
+    /// falseにするとテストのプロセス内のスレッドで解答する 速いが、スタックオーバーフローで
+    /// テストごと異常終了し、TLEとなったスレッドは終了されずに残る
+    /// `INTERACTOR`を指定した場合は常にプロセス内で解答する
+    const ISOLATED: bool = true;
     fn solve(read: impl Read, write: impl Write);
     fn assert(input: &str, expect: &str) {
-        let mut buf = Vec::new();
-        Self::solve(input.as_bytes(), &mut buf);
-        let assert = StaticAssertion {
-            input: Cow::Borrowed(input),
-            expect: Cow::Borrowed(expect),
-            eps: Self::EPSILON,
-        };
-        assert!(assert.assert(&String::from_utf8_lossy(&buf)).expect(""))
+        judge::assert_solver::<Self>(input, expect)
     }
 }
 
 pub trait Verifiable: Solver {
     type SERVICE: Service;
+    /// 隔離実行で子プロセスから呼び出すテスト関数のパス
+    const ENTRY_POINT: Option<&'static str> = None;
 
     fn save_metadata() -> anyhow::Result<()> {
         let info_path = Self::SERVICE::info_path();
@@ -64,42 +91,179 @@ pub trait Verifiable: Solver {
             .create(true)
             .append(true)
             .open(info_path)?
-            .write(format!("{}\n", Self::PROBLEM_ID).as_bytes())?;
+            .write_all(format!("{}\n", Self::PROBLEM_ID).as_bytes())?;
         Ok(())
     }
-    fn verify_inner(read: &[u8], write: &mut Vec<u8>) {
+    fn verify_inner(read: &mut dyn Read, write: &mut dyn Write) {
         Self::solve(read, write)
     }
+    fn attribute() -> VerifyAttribute {
+        VerifyAttribute {
+            problem_id: Self::PROBLEM_ID.to_string(),
+            epsilon: Self::EPSILON,
+            time_limit_ms: Self::TIME_LIMIT_MILLIS,
+            memory_limit_mb: Self::MEMORY_LIMIT_MB,
+            stack_size: Self::STACK_SIZE,
+            output_limit_bytes: Self::OUTPUT_LIMIT_BYTES,
+            compare_mode: Self::COMPARE_MODE,
+            repeat: Self::REPEAT,
+            checker: Self::CHECKER,
+            interactor: Self::INTERACTOR,
+            artifact_dir: Some(artifact::problem_dir(
+                Self::SERVICE::SERVICE_NAME,
+                Self::PROBLEM_ID,
+            )),
+            // エントリポイントがないか対話する場合は隔離実行できないため、プロセス内で解答する
+            isolated: Self::ISOLATED && Self::ENTRY_POINT.is_some() && Self::INTERACTOR.is_none(),
+            entry_point: Self::ENTRY_POINT.map(str::to_string),
+        }
+    }
     fn verify() -> anyhow::Result<VerifyResult> {
-        Self::SERVICE::verify(
-            VerifyAttribute {
-                problem_id: Self::PROBLEM_ID.to_string(),
-                epsilon: Self::EPSILON,
-                time_limit_ms: Self::TIME_LIMIT_MILLIS,
-            },
-            Self::verify_inner,
-        )
+        let attr = Self::attribute();
+        judge::isolated::serve_if_child(Self::verify_inner, &attr);
+        // ケースの実行と重ならないよう、判定を始める前に計測する
+        calibration::get(VerifyConfig::get());
+        let mut result = Self::SERVICE::verify(attr, Self::verify_inner)?;
+        // リポジトリに保存したケースも判定する
+        let dir = match local_cases::problem_dir(Self::SERVICE::SERVICE_NAME, Self::PROBLEM_ID) {
+            Ok(dir) => dir,
+            Err(e) => {
+                println!("could not find local test cases: {e}");
+                return Ok(result);
+            }
+        };
+        if VerifyConfig::get().fail_fast && result.outcome() == VerifyOutcome::Failed {
+            result.skipped += local_cases::names(&dir).len();
+        } else {
+            result.merge(local_cases::verify(
+                &dir,
+                Self::attribute(),
+                Self::verify_inner,
+            ));
+        }
+        Ok(result)
+    }
+    /// 入力と想定解の出力をリポジトリのテストケースとして保存し、以後の判定に含める
+    fn save_case(input: &str, expect: &str) -> anyhow::Result<String> {
+        let dir = local_cases::problem_dir(Self::SERVICE::SERVICE_NAME, Self::PROBLEM_ID)?;
+        local_cases::save(&dir, input.as_bytes(), expect.as_bytes())
+    }
+    /// 想定解`R`の出力を想定解の出力としてテストケースを保存する
+    fn save_case_with<R: Solver>(input: &str) -> anyhow::Result<String> {
+        let mut expect = Vec::new();
+        R::solve(input.as_bytes(), &mut expect);
+        Self::save_case(input, &String::from_utf8_lossy(&expect))
     }
     fn output(res: &VerifyResult, path: &str, ident: &str) -> anyhow::Result<()> {
         let mut md_path = PathBuf::from_str(&crate::workspace_root_directory()?)?;
         md_path.push(path);
         md_path.pop();
         md_path.push(format!("result_{ident}.md"));
+        if matches!(
+            res.outcome(),
+            VerifyOutcome::NoTestCases | VerifyOutcome::Skipped
+        ) {
+            println!("{}: {}", Self::PROBLEM_ID, res.outcome());
+        }
         File::create(md_path)?.write_all(Self::generate_md(res).as_bytes())?;
         Ok(())
     }
 
     fn generate_md(res: &VerifyResult) -> String {
-        let (icon, url, tl) = (
+        let (icon, url, tl, ml) = (
             res.result_icon(),
             Self::SERVICE::url(Self::PROBLEM_ID),
             Self::TIME_LIMIT_MILLIS,
+            Self::MEMORY_LIMIT_MB,
         );
+        // 判定しなかったケースがあれば明記し、空の結果を合格に見せない
+        let skipped = match res.outcome() {
+            VerifyOutcome::NoTestCases => "no test cases were found\n\n".to_string(),
+            VerifyOutcome::Skipped => format!("all {} cases were skipped\n\n", res.skipped),
+            _ if res.skipped > 0 => format!("{} cases were skipped\n\n", res.skipped),
+            _ => String::new(),
+        };
+        let config = VerifyConfig::get();
+        let basis = match config.time_basis {
+            TimeBasis::Wall => "",
+            TimeBasis::Cpu => " (cpu time)",
+        };
+        // 倍率を掛けた場合は実際に適用した制限を元の制限と併記する
+        let multiplier = config.time_limit_multiplier();
+        let tl = if multiplier == 1.0 {
+            format!("{tl}ms")
+        } else {
+            format!("{}ms ({tl}ms x {multiplier:.2})", config.time_limit_ms(tl))
+        };
+        let calibration = calibration::get(config).map_or(String::new(), |calibration| {
+            format!(
+                "speed factor: {:.2} (reference workload {:.1}ms, baseline {:.1}ms)\n\n",
+                calibration.speed_factor(),
+                calibration.measured_ms,
+                calibration.baseline_ms
+            )
+        });
+        let left_running = match judge::left_running_solvers() {
+            0 => String::new(),
+            n => format!(
+                "{n} solver threads are left running after TLE, run isolated to kill them\n\n"
+            ),
+        };
+        // メモリ使用量は隔離実行したケースでのみ計測し、制限を適用する
+        let ml_note = if res.cases.iter().any(|case| case.peak_memory_kb.is_some()) {
+            ""
+        } else {
+            " (not measured)"
+        };
         let mut body = String::new();
         for case in &res.cases {
+            let mut exec_time = format!("{}ms", case.exec_time_ms);
+            let mut cpu_time = case
+                .cpu_time_ms
+                .map_or("-".to_string(), |ms| format!("{ms}ms"));
+            // 繰り返し実行した場合は判定に使った実行時間に分布を添える
+            if let Some(stats) = case.time_stats {
+                let spread = format!(
+                    " ({}-{}ms, {} runs)",
+                    stats.min_ms, stats.max_ms, stats.runs
+                );
+                match config.time_basis {
+                    TimeBasis::Wall => exec_time.push_str(&spread),
+                    TimeBasis::Cpu => cpu_time.push_str(&spread),
+                }
+            }
+            let message = match (&case.message, &case.diagnostic) {
+                (Some(message), _) => message.clone(),
+                (None, Some(diagnostic)) => diagnostic.to_string(),
+                (None, None) => String::new(),
+            };
+            let links = case
+                .artifacts
+                .iter()
+                .map(|path| {
+                    let extension = path.extension().unwrap_or_default().to_string_lossy();
+                    format!(" [{extension}](<{}>)", path.display())
+                })
+                .collect::<String>();
+            let memory = case
+                .peak_memory_kb
+                .map_or("-".to_string(), |kb| format!("{:.1}MB", kb as f64 / 1024.0));
+            let near_limit = if case.near_limit { " (near limit)" } else { "" };
+            let source = match case.source {
+                CaseSource::Fetched => "",
+                CaseSource::Local => " (local)",
+            };
             body.push_str(&format!(
-                "| {} | {} | {}ms |\n",
-                case.name, case.status, case.exec_time_ms
+                "| {}{} | {}{} | {} | {} | {} | {}{} |\n",
+                case.name,
+                source,
+                case.status,
+                near_limit,
+                exec_time,
+                cpu_time,
+                memory,
+                markdown_cell(&message),
+                links
             ));
         }
         let footer = format!(
@@ -109,14 +273,20 @@ pub trait Verifiable: Solver {
         format!(
             "# Verify Result {icon}\n
 ## [PROBLEM LINK]({url})\n
-TL: {tl}ms\n
-| case name | judge | elapsed time |
-| :--- | :---: | ---: |
+{skipped}TL: {tl}{basis}\n
+{calibration}{left_running}ML: {ml}MB{ml_note}\n
+| case name | judge | elapsed time | cpu time | memory | message |
+| :--- | :---: | ---: | ---: | ---: | :--- |
 {body}\n{footer}\n",
         )
     }
 }
 
+/// 表のセルを壊さないよう、区切り文字と改行をエスケープする
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
 fn workspace_root_directory() -> anyhow::Result<String> {
     #[derive(Debug, Clone, Deserialize)]
     struct TargetDir {
@@ -154,3 +324,28 @@ fn save_temp_file(buf: &[u8]) -> anyhow::Result<NamedTempFile> {
 pub struct ProblemForVerify {
     pub problem_id: String,
 }
+
+#[test]
+fn isolated_attribute_test() {
+    use service::aizu_online_judge::AizuOnlineJudge;
+
+    struct Derived;
+    impl Solver for Derived {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(_read: impl Read, _write: impl Write) {}
+    }
+    impl Verifiable for Derived {
+        type SERVICE = AizuOnlineJudge;
+        const ENTRY_POINT: Option<&'static str> = Some("verify_core::verify_Derived");
+    }
+    struct Manual;
+    impl Solver for Manual {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(_read: impl Read, _write: impl Write) {}
+    }
+    impl Verifiable for Manual {
+        type SERVICE = AizuOnlineJudge;
+    }
+    assert!(Derived::attribute().isolated);
+    assert!(!Manual::attribute().isolated);
+}
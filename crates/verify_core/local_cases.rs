@@ -0,0 +1,111 @@
+use crate::{
+    attribute::VerifyAttribute,
+    judge::{CaseSource, VerifyResult},
+    service, SolveFunc,
+};
+use anyhow::Context;
+use std::{
+    fs::{create_dir_all, read_dir, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// リポジトリ内でテストケースを置くディレクトリ
+const TESTCASES_DIR: &str = "testcases";
+/// 保存するケース名の接頭辞
+const CASE_PREFIX: &str = "regression_";
+
+/// 問題ごとのテストケースのディレクトリ `testcases/<service>/<problem_id>`
+/// 入力を`in/<name>`、想定解の出力を`out/<name>`に置く
+pub fn problem_dir(service_name: &str, problem_id: &str) -> anyhow::Result<PathBuf> {
+    Ok(testcases_dir()?.join(service_name).join(problem_id))
+}
+
+/// ワークスペースの`testcases`ディレクトリ
+pub fn testcases_dir() -> anyhow::Result<PathBuf> {
+    Ok(PathBuf::from(crate::workspace_root_directory()?).join(TESTCASES_DIR))
+}
+
+/// 入力と想定解の出力を、使われていない番号のケースとして保存し、そのケース名を返す
+pub fn save(dir: &Path, input: &[u8], expect: &[u8]) -> anyhow::Result<String> {
+    let (in_dir, out_dir) = (dir.join("in"), dir.join("out"));
+    create_dir_all(&in_dir).with_context(|| "could not create in directory")?;
+    create_dir_all(&out_dir).with_context(|| "could not create out directory")?;
+    let name = (1..)
+        .map(|i| format!("{CASE_PREFIX}{i:03}"))
+        .find(|name| !in_dir.join(name).exists() && !out_dir.join(name).exists())
+        .unwrap();
+    File::create(in_dir.join(&name))?.write_all(input)?;
+    File::create(out_dir.join(&name))?.write_all(expect)?;
+    println!("saved test case {}", in_dir.join(&name).display());
+    Ok(name)
+}
+
+/// 入力のファイルがあるケース名 ディレクトリがなければ空
+pub fn names(dir: &Path) -> Vec<String> {
+    let mut names = read_dir(dir.join("in"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// ディレクトリのケースを判定する 結果には置き場所を記録する
+pub(crate) fn verify(dir: &Path, mut attr: VerifyAttribute, f: SolveFunc) -> VerifyResult {
+    // 取得したケースと名前が重なっても成果物を上書きしないよう、分けて保存する
+    attr.artifact_dir = attr.artifact_dir.map(|dir| dir.join(TESTCASES_DIR));
+    let mut result = service::verify_cases(
+        &names(dir),
+        String::as_str,
+        |name| Ok((dir.join("in").join(name), dir.join("out").join(name))),
+        &attr,
+        f,
+    );
+    for case in &mut result.cases {
+        case.source = CaseSource::Local;
+    }
+    result
+}
+
+#[test]
+fn save_test() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(names(dir.path()).is_empty());
+    assert_eq!(save(dir.path(), b"1\n", b"2\n").unwrap(), "regression_001");
+    assert_eq!(save(dir.path(), b"3\n", b"4\n").unwrap(), "regression_002");
+    assert_eq!(names(dir.path()), ["regression_001", "regression_002"]);
+    let out = std::fs::read(dir.path().join("out").join("regression_002")).unwrap();
+    assert_eq!(out, b"4\n");
+}
+
+#[test]
+fn verify_test() {
+    use crate::judge::{self, JudgeStatus};
+    use std::io::Read;
+
+    fn echo(read: &mut dyn Read, write: &mut dyn Write) {
+        std::io::copy(read, write).unwrap();
+    }
+    let dir = tempfile::tempdir().unwrap();
+    save(dir.path(), b"1\n", b"1\n").unwrap();
+    save(dir.path(), b"2\n", b"3\n").unwrap();
+    File::create(dir.path().join("in").join("no_output")).unwrap();
+    let result = verify(dir.path(), judge::test_attr(None), echo);
+    let statuses = result
+        .cases
+        .iter()
+        .map(|case| (case.status.clone(), case.source))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        [
+            (JudgeStatus::MissingFiles, CaseSource::Local),
+            (JudgeStatus::Accepted, CaseSource::Local),
+            (JudgeStatus::WrongAnswer, CaseSource::Local),
+        ]
+    );
+}
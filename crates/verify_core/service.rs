@@ -2,3 +2,75 @@ pub mod aizu_online_judge;
 pub mod atcoder;
 pub mod library_checker;
 pub mod yukicoder;
+
+use crate::{
+    attribute::VerifyAttribute,
+    config::VerifyConfig,
+    judge::{self, pool, JudgeResult, JudgeStatus, VerifyResult},
+    SolveFunc,
+};
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// 保存したJSONのファイルを読む
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let mut buf = Vec::new();
+    File::open(path)
+        .with_context(|| "could not open header file")?
+        .read_to_end(&mut buf)
+        .with_context(|| "could not load file")?;
+    serde_json::from_slice(&buf).with_context(|| "saved header file is invalid")
+}
+
+/// 取得したケースの一覧を読む ヘッダーがなければ取得したケースは0件としてNoneを返す
+pub(crate) fn read_header<T: DeserializeOwned>(path: &Path) -> anyhow::Result<Option<T>> {
+    if !path.exists() {
+        println!("header file is not found {}", path.display());
+        return Ok(None);
+    }
+    read_json(path).map(Some)
+}
+
+/// 設定に従ってケースを選び、`paths`で得た入力と想定解のファイルで並列に判定する
+pub(crate) fn verify_cases<T: Sync>(
+    cases: &[T],
+    name: impl Fn(&T) -> &str + Sync,
+    paths: impl Fn(&T) -> anyhow::Result<(PathBuf, PathBuf)> + Sync,
+    attr: &VerifyAttribute,
+    f: SolveFunc,
+) -> VerifyResult {
+    let config = VerifyConfig::get();
+    let selected = config.select(cases, &name);
+    let results = pool::run(
+        &selected,
+        config.jobs(),
+        config.fail_fast,
+        |runtime, case| {
+            let name = name(case);
+            let (in_path, out_path) = match paths(case) {
+                Ok(paths) => paths,
+                Err(e) => {
+                    let mut ret = JudgeResult::new(name.to_string(), JudgeStatus::InternalError);
+                    ret.message = Some(e.to_string());
+                    return ret;
+                }
+            };
+            match judge::read_case(name, &in_path, &out_path) {
+                Ok((input, expect)) => runtime.block_on(judge::verify_case(
+                    name.to_string(),
+                    &input,
+                    &expect,
+                    attr,
+                    f,
+                )),
+                Err(missing) => *missing,
+            }
+        },
+    );
+    VerifyResult::new(results, cases.len())
+}
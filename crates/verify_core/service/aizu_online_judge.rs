@@ -1,11 +1,6 @@
-use crate::{
-    attribute::VerifyAttribute,
-    judge::{self, JudgeResult, JudgeStatus, StaticAssertion, VerifyResult},
-    Service, SolveFunc,
-};
+use crate::{attribute::VerifyAttribute, judge::VerifyResult, service, Service, SolveFunc};
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::Read, path::PathBuf};
-use tokio::runtime::Builder;
+use std::path::PathBuf;
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct AOJTestCaseHeaders {
@@ -30,10 +25,22 @@ impl Service for AizuOnlineJudge {
     }
 
     fn verify(attr: VerifyAttribute, f: SolveFunc) -> anyhow::Result<VerifyResult> {
-        let mut buf = Vec::new();
-        File::open(Self::header_path(&attr.problem_id)?)?.read_to_end(&mut buf)?;
-        let headers: AOJTestCaseHeaders = serde_json::from_slice(&buf)?;
-        Ok(headers.verify(&attr, f))
+        let header_path = Self::header_path(&attr.problem_id)?;
+        let Some(headers) = service::read_header::<AOJTestCaseHeaders>(&header_path)? else {
+            return Ok(VerifyResult::new(vec![], 0));
+        };
+        Ok(service::verify_cases(
+            &headers.headers,
+            |header| &header.name,
+            |header| {
+                Ok((
+                    header.in_path(&attr.problem_id)?,
+                    header.out_path(&attr.problem_id)?,
+                ))
+            },
+            &attr,
+            f,
+        ))
     }
     const SERVICE_NAME: &'static str = "aizu_online_judge";
 }
@@ -52,57 +59,7 @@ impl AizuOnlineJudge {
     }
 }
 
-impl AOJTestCaseHeaders {
-    fn verify(&self, attr: &VerifyAttribute, f: SolveFunc) -> VerifyResult {
-        let cases: Vec<_> = self
-            .headers
-            .iter()
-            .map(|header| {
-                header.verify(attr, f).unwrap_or(JudgeResult {
-                    name: header.name.clone(),
-                    status: JudgeStatus::InternalError,
-                    exec_time_ms: 0,
-                })
-            })
-            .collect();
-        VerifyResult { cases }
-    }
-}
-
 impl AOJTestCaseHeader {
-    fn verify(&self, attr: &VerifyAttribute, f: SolveFunc) -> anyhow::Result<JudgeResult> {
-        let in_path = self.in_path(&attr.problem_id)?;
-        let out_path = self.out_path(&attr.problem_id)?;
-        let input_buf = crate::read_file(&in_path).unwrap_or_else(|_e| {
-            println!("in file is not found {}:{}", attr.problem_id, self.name);
-            Vec::new()
-        });
-        let expect_buf = crate::read_file(&out_path).unwrap_or_else(|_e| {
-            println!("out file is not found {}:{}", attr.problem_id, self.name);
-            Vec::new()
-        });
-        let input = String::from_utf8_lossy(&input_buf);
-        let expect = String::from_utf8_lossy(&expect_buf);
-        let assertion = StaticAssertion {
-            input,
-            expect,
-            eps: attr.epsilon,
-        };
-        if in_path.exists() && out_path.exists() {
-            Ok(Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap()
-                .block_on(judge::verify_inner(self.name.clone(), &assertion, attr, f)))
-        } else {
-            Ok(JudgeResult {
-                name: self.name.clone(),
-                status: JudgeStatus::InternalError,
-                exec_time_ms: 0,
-            })
-        }
-    }
-
     pub fn in_path(&self, problem_id: &str) -> anyhow::Result<PathBuf> {
         Ok(AizuOnlineJudge::problem_dir_path(problem_id)?
             .join("in")
@@ -1,13 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::{
-    fs::{create_dir_all, File},
-    io::Read,
+    fs::create_dir_all,
     path::{Path, PathBuf},
 };
 
 use anyhow::Context;
 
-use crate::{judge::VerifyResult, Service};
+use crate::{attribute::VerifyAttribute, judge::VerifyResult, service, Service, SolveFunc};
 
 pub struct AtCoder;
 impl Service for AtCoder {
@@ -15,17 +14,14 @@ impl Service for AtCoder {
     fn url(_problem_id: &str) -> String {
         "https://atcoder.jp/".to_string()
     }
-    fn verify(
-        attr: crate::attribute::VerifyAttribute,
-        _f: crate::SolveFunc,
-    ) -> anyhow::Result<crate::judge::VerifyResult> {
+    fn verify(attr: VerifyAttribute, f: SolveFunc) -> anyhow::Result<VerifyResult> {
         let problem_dir =
             create_problem_directory(&attr.problem_id, &crate::app_cache_directory())?;
-        let path = header_path(&problem_dir);
-        let _cases = AtCoderHeader::from_file(&path);
-        // Ok(cases.verify(&attr, &problem_dir, f))
-        Ok(VerifyResult { cases: Vec::new() })
-        // }
+        let Some(header) = service::read_header::<AtCoderHeader>(&header_path(&problem_dir))?
+        else {
+            return Ok(VerifyResult::new(vec![], 0));
+        };
+        Ok(header.verify(&attr, &problem_dir, f))
     }
 }
 
@@ -51,12 +47,18 @@ pub fn header_path(problem_dir: &Path) -> PathBuf {
 }
 
 impl AtCoderHeader {
-    fn from_file(path: &PathBuf) -> Self {
-        let mut buf = Vec::new();
-        File::open(path)
-            .expect("header file is not found")
-            .read_to_end(&mut buf)
-            .expect("could not load file");
-        serde_json::from_slice(&buf).expect("saved header file is invalid")
+    fn verify(&self, attr: &VerifyAttribute, problem_dir: &Path, f: SolveFunc) -> VerifyResult {
+        service::verify_cases(
+            &self.list,
+            String::as_str,
+            |name| {
+                Ok((
+                    problem_dir.join("in").join(name),
+                    problem_dir.join("out").join(name),
+                ))
+            },
+            attr,
+            f,
+        )
     }
 }
@@ -1,15 +1,18 @@
 use crate::{
     attribute::VerifyAttribute,
-    judge::{Assertion, CheckBinaryAssertion, JudgeResult, JudgeStatus, VerifyResult},
+    config::VerifyConfig,
+    judge::{
+        self, interactive::Interaction, pool, Assertion, CheckBinaryAssertion, JudgeResult,
+        VerifyResult,
+    },
     Service, SolveFunc,
 };
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{read_dir, read_to_string},
-    path::{Path, PathBuf},
-    time::Duration,
+    path::PathBuf,
 };
-use tokio::{runtime, time};
+use tokio::runtime::Runtime;
 
 pub struct LibraryChecker;
 
@@ -20,8 +23,14 @@ impl Service for LibraryChecker {
         format!("https://judge.yosupo.jp/problem/{problem_id}")
     }
     fn verify(attr: VerifyAttribute, f: crate::SolveFunc) -> anyhow::Result<VerifyResult> {
-        let problem = find_problem(&attr.problem_id)?;
-        Ok(problem.verify(&attr, f))
+        // 取得していない問題は取得したケースが0件として扱う
+        match lookup_problem(&attr.problem_id)? {
+            Some(problem) => Ok(problem.verify(&attr, f)),
+            None => {
+                println!("info.toml is not found for {}", attr.problem_id);
+                Ok(VerifyResult::new(vec![], 0))
+            }
+        }
     }
 }
 
@@ -33,13 +42,69 @@ pub struct Problem {
 
 impl Problem {
     fn verify(&self, attr: &VerifyAttribute, f: SolveFunc) -> VerifyResult {
-        let cases = self
-            .info
-            .tests
-            .iter()
-            .flat_map(|c| c.verify(&self.dir, attr, f))
-            .collect();
-        VerifyResult { cases }
+        let names: Vec<_> = self.info.tests.iter().flat_map(TestCase::names).collect();
+        let config = VerifyConfig::get();
+        let selected = config.select(&names, String::as_str);
+        let cases = pool::run(
+            &selected,
+            config.jobs(),
+            config.fail_fast,
+            |runtime, name| self.verify_case(runtime, name, attr, f),
+        );
+        VerifyResult::new(cases, names.len())
+    }
+
+    fn verify_case(
+        &self,
+        runtime: &Runtime,
+        name: &str,
+        attr: &VerifyAttribute,
+        f: SolveFunc,
+    ) -> JudgeResult {
+        let in_path = self.dir.join("in").join(name).with_extension("in");
+        let out_path = self.dir.join("out").join(name).with_extension("out");
+        let (in_buf, out_buf) = match judge::read_case(name, &in_path, &out_path) {
+            Ok(bufs) => bufs,
+            Err(missing) => return *missing,
+        };
+        let interactor_path = self.dir.join("interactor");
+        let interaction = match attr.interactor {
+            Some(interactor) => Some(Interaction::Native(interactor)),
+            None if interactor_path.exists() => Some(Interaction::Binary(&interactor_path)),
+            None => None,
+        };
+        if let Some(interaction) = interaction {
+            return runtime.block_on(judge::interactive::verify_inner(
+                name.to_string(),
+                &in_buf,
+                &out_buf,
+                interaction,
+                attr,
+                f,
+            ));
+        }
+        // checkerか比較方法が指定されていればcheckerバイナリを使わずに判定する
+        let assertion: Box<dyn Assertion> = if attr.checker.is_some() || attr.compare_mode.is_some()
+        {
+            judge::assertion(
+                String::from_utf8_lossy(&in_buf),
+                String::from_utf8_lossy(&out_buf),
+                attr,
+            )
+        } else {
+            Box::new(CheckBinaryAssertion {
+                input_path: in_path.clone(),
+                expect_path: out_path.clone(),
+                checker_path: self.dir.join("checker"),
+            })
+        };
+        runtime.block_on(judge::verify_inner(
+            name.to_string(),
+            &in_buf,
+            assertion.as_ref(),
+            attr,
+            f,
+        ))
     }
 }
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -54,7 +119,8 @@ struct TestCase {
 }
 
 impl TestCase {
-    fn verify(&self, root_dir: &Path, attr: &VerifyAttribute, f: SolveFunc) -> Vec<JudgeResult> {
+    /// 拡張子を除いたケースのファイル名
+    fn names(&self) -> Vec<String> {
         fn case_file_name(name: &str, i: usize) -> String {
             let mut iter = name.rsplitn(2, '.');
             let after = iter.next();
@@ -67,115 +133,33 @@ impl TestCase {
             .map(|name| format!("{}_{:02}", name, i))
             .unwrap()
         }
-        let in_dir = root_dir.join("in");
-        let out_dir = root_dir.join("out");
-
-        let mut ret = Vec::new();
-        for i in 0..self.number {
-            let name = case_file_name(&self.name, i);
-            let in_path = in_dir.join(&name).with_extension("in");
-            let out_path = out_dir.join(&name).with_extension("out");
-            if !in_path.exists() {
-                println!("in file is not found: {}", &in_path.to_string_lossy());
-            }
-            if !out_path.exists() {
-                println!("out file is not found: {}", &out_path.to_string_lossy());
-            }
-            let assertion = CheckBinaryAssertion {
-                input_path: in_path.clone(),
-                expect_path: out_path.clone(),
-                checker_path: root_dir.join("checker"),
-            };
-            if in_path.exists() && out_path.exists() {
-                ret.push(
-                    runtime::Builder::new_current_thread()
-                        .enable_all()
-                        .build()
-                        .unwrap()
-                        .block_on(self.verify_inner(name, &assertion, attr, f)),
-                )
-            } else {
-                ret.push(JudgeResult {
-                    name,
-                    status: JudgeStatus::InternalError,
-                    exec_time_ms: 0,
-                });
-            }
-        }
-        ret
-    }
-
-    async fn verify_inner(
-        &self,
-        name: String,
-        assertion: &CheckBinaryAssertion,
-        attr: &VerifyAttribute,
-        f: SolveFunc,
-    ) -> JudgeResult {
-        let mut ret = JudgeResult {
-            name,
-            status: JudgeStatus::InternalError,
-            exec_time_ms: 0,
-        };
-        let Ok(in_buf) = crate::read_file(&assertion.input_path) else {
-            return ret;
-        };
-
-        let run = async {
-            let now = time::Instant::now();
-            let actual = ::std::panic::catch_unwind(|| {
-                let mut actual = Vec::new();
-                f(&in_buf, &mut actual);
-                actual
-            });
-            (actual, now.elapsed())
-        };
-        let sleep = time::sleep(Duration::from_millis(attr.time_limit_ms));
-        tokio::select! {
-            _ = sleep => {
-                // うまく動作していない 度を越えたTLEはこちらで打ち切りたい
-                ret.status = JudgeStatus::TimeLimitExceeded
-            },
-            (actual, elapsed) = run => {
-                ret.exec_time_ms = elapsed.as_millis() as u64;
-                if let Ok(actual) = actual {
-                    match assertion.assert(&String::from_utf8_lossy(&actual)) {
-                        Ok(status) => {
-                            if status && ret.exec_time_ms <= attr.time_limit_ms {
-                                ret.status = JudgeStatus::Accepted
-                            } else if !status {
-                                ret.status = JudgeStatus::WrongAnswer
-                            } else {
-                                ret.status = JudgeStatus::TimeLimitExceeded
-                            }
-                        }
-                        Err(e) => {
-                            println!("{:?}", e);
-                            ret.status = JudgeStatus::InternalError
-                        }
-                    }
-                } else {
-                    ret.status = JudgeStatus::RuntimeError
-                }
-            },
-
-        }
-        ret
+        (0..self.number)
+            .map(|i| case_file_name(&self.name, i))
+            .collect()
     }
 }
 
 pub fn find_problem(problem_id: &str) -> anyhow::Result<Problem> {
-    for entry in read_dir(root_dir()?)?.flatten() {
+    lookup_problem(problem_id)?.ok_or_else(|| anyhow::format_err!("info.toml is not found."))
+}
+
+/// 取得済みの問題を探す 見つからなければNone
+fn lookup_problem(problem_id: &str) -> anyhow::Result<Option<Problem>> {
+    let root_dir = root_dir()?;
+    if !root_dir.exists() {
+        return Ok(None);
+    }
+    for entry in read_dir(root_dir)?.flatten() {
         let mut path = entry.path().join(problem_id).join("info.toml");
         if path.is_file() {
             log::debug!("found problem: {}", path.display());
             let data = read_to_string(&path)?;
             let info: ProblemInfo = toml::from_str(&data)?;
             path.pop();
-            return Ok(Problem { dir: path, info });
+            return Ok(Some(Problem { dir: path, info }));
         }
     }
-    Err(anyhow::format_err!("info.toml is not found."))
+    Ok(None)
 }
 
 pub fn root_dir() -> anyhow::Result<PathBuf> {
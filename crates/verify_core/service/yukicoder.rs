@@ -1,17 +1,11 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::{
-    fs::{create_dir_all, File},
-    io::Read,
+    fs::create_dir_all,
     path::{Path, PathBuf},
 };
-use tokio::runtime;
 
-use crate::{
-    attribute::VerifyAttribute,
-    judge::{self, JudgeResult, JudgeStatus, StaticAssertion, VerifyResult},
-    Service, SolveFunc,
-};
+use crate::{attribute::VerifyAttribute, judge::VerifyResult, service, Service, SolveFunc};
 
 pub struct Yukicoder;
 
@@ -23,9 +17,11 @@ impl Service for Yukicoder {
     fn verify(attr: VerifyAttribute, f: SolveFunc) -> anyhow::Result<VerifyResult> {
         let problem_dir =
             create_problem_directory(&attr.problem_id, &crate::app_cache_directory())?;
-        let path = header_path(&problem_dir);
-        let cases = YukicoderHeader::from_file(&path);
-        Ok(cases.verify(&attr, &problem_dir, f))
+        let Some(header) = service::read_header::<YukicoderHeader>(&header_path(&problem_dir))?
+        else {
+            return Ok(VerifyResult::new(vec![], 0));
+        };
+        Ok(header.verify(&attr, &problem_dir, f))
     }
 }
 
@@ -35,64 +31,22 @@ pub struct YukicoderHeader {
     pub list: Vec<String>,
 }
 impl YukicoderHeader {
-    pub fn from_file(path: &PathBuf) -> Self {
-        let mut buf = Vec::new();
-        File::open(path)
-            .expect("header file is not found")
-            .read_to_end(&mut buf)
-            .expect("could not load file");
-        serde_json::from_slice(&buf).expect("saved header file is invalid")
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        service::read_json(path)
     }
     fn verify(&self, attr: &VerifyAttribute, problem_dir: &Path, f: SolveFunc) -> VerifyResult {
-        let cases: Vec<_> = self
-            .list
-            .iter()
-            .map(|case_name| verify(attr, problem_dir, case_name, f))
-            .collect();
-        VerifyResult { cases }
-    }
-}
-
-fn verify(
-    attr: &VerifyAttribute,
-    problem_dir: &Path,
-    case_name: &str,
-    f: SolveFunc,
-) -> JudgeResult {
-    let in_path = problem_dir.join("in").join(case_name);
-    let out_path = problem_dir.join("out").join(case_name);
-    let input_buf = crate::read_file(&in_path).unwrap_or_else(|_e| {
-        println!("in file is not found {}:{case_name}", attr.problem_id);
-        Vec::new()
-    });
-    let expect_buf = crate::read_file(&out_path).unwrap_or_else(|_e| {
-        println!("out file is not found {}:{case_name}", attr.problem_id);
-        Vec::new()
-    });
-    let input = String::from_utf8_lossy(&input_buf);
-    let expect = String::from_utf8_lossy(&expect_buf);
-    let assertion = StaticAssertion {
-        input,
-        expect,
-        eps: attr.epsilon,
-    };
-    if in_path.exists() && out_path.exists() {
-        runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .unwrap()
-            .block_on(judge::verify_inner(
-                case_name.to_string(),
-                &assertion,
-                attr,
-                f,
-            ))
-    } else {
-        JudgeResult {
-            name: case_name.to_string(),
-            status: JudgeStatus::InternalError,
-            exec_time_ms: 0,
-        }
+        service::verify_cases(
+            &self.list,
+            String::as_str,
+            |name| {
+                Ok((
+                    problem_dir.join("in").join(name),
+                    problem_dir.join("out").join(name),
+                ))
+            },
+            attr,
+            f,
+        )
     }
 }
 
@@ -0,0 +1,386 @@
+use crate::{
+    config::VerifyConfig,
+    judge::{self, diagnostic::Diagnostic, Termination},
+    Solver, Verifiable,
+};
+use anyhow::anyhow;
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter, Result},
+    io::{self, Read, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::runtime::Runtime;
+
+/// 反例を縮小する際、1つの大きさで試すシードの数
+const SHRINK_SEEDS: u64 = 32;
+/// 反例を縮小する際、この大きさまでは全ての大きさを試し、それ以降は等比的に試す
+const SHRINK_LINEAR_SIZES: usize = 64;
+
+/// シードと大きさから入力を作る クロージャ`Fn(u64, usize) -> String`も使える
+/// 大きさの意味は問わないが、小さいほど入力が小さくなるようにする
+pub trait Generator {
+    fn generate(&self, seed: u64, size: usize) -> String;
+}
+
+impl<F: Fn(u64, usize) -> String> Generator for F {
+    fn generate(&self, seed: u64, size: usize) -> String {
+        self(seed, size)
+    }
+}
+
+/// ストレステストを打ち切る条件
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// シード`0..n`を試す
+    Iterations(u64),
+    /// 経過時間が超えるまでシード0から順に試す
+    Time(Duration),
+}
+
+/// 想定解と解答の出力が異なった入力
+#[derive(Clone, Debug)]
+pub struct Counterexample {
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    /// 想定解の出力
+    pub expect: String,
+    /// 解答の出力 panicした場合はそれまでの出力
+    pub actual: String,
+    pub diagnostic: Diagnostic,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "seed {}, size {}: {}\ninput:\n{}\nexpect:\n{}\nactual:\n{}",
+            self.seed, self.size, self.diagnostic, self.input, self.expect, self.actual
+        )
+    }
+}
+
+impl Counterexample {
+    /// 入力と想定解の出力を`V`のリポジトリのテストケースとして保存し、そのケース名を返す
+    pub fn save<V: Verifiable>(&self) -> anyhow::Result<String> {
+        V::save_case(&self.input, &self.expect)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StressReport {
+    /// 試した入力の数
+    pub iterations: u64,
+    /// 見つかった反例のうち、最も小さいもの
+    pub counterexample: Option<Counterexample>,
+    /// 縮小する前の、最初に見つかった反例 縮小できなかった場合はNone
+    pub original: Option<Counterexample>,
+}
+
+/// `generator`で作った入力で想定解`R`と解答`C`を実行し、出力を比較する
+/// 大きさは1から`max_size`までを順に繰り返す 出力が異なれば、より小さい大きさで反例を探し直す
+/// 比較には解答の`CHECKER`、`EPSILON`、`COMPARE_MODE`を使う
+/// 各解答は実行時間制限で打ち切り、`Budget::Time`の場合は残りの時間でも打ち切る
+/// 解答が実行時間制限を超えた場合は反例とし、想定解がpanicするか制限を超えた場合はErrを返す
+pub fn stress<R: Solver, C: Solver>(
+    generator: impl Generator,
+    max_size: usize,
+    budget: Budget,
+) -> anyhow::Result<StressReport> {
+    let max_size = max_size.max(1);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let deadline = match budget {
+        Budget::Iterations(_) => None,
+        Budget::Time(limit) => Some(Instant::now() + limit),
+    };
+    let mut iterations = 0;
+    for seed in 0.. {
+        let exhausted = match budget {
+            Budget::Iterations(n) => seed >= n,
+            Budget::Time(_) => deadline.is_some_and(|deadline| Instant::now() >= deadline),
+        };
+        if exhausted {
+            break;
+        }
+        let size = seed as usize % max_size + 1;
+        let original = match run::<R, C>(&runtime, seed, size, &generator, deadline)? {
+            Trial::Passed => {
+                iterations += 1;
+                continue;
+            }
+            Trial::Failed(original) => original,
+            Trial::OutOfBudget => break,
+        };
+        iterations += 1;
+        println!("stress test failed after {iterations} inputs at seed {seed}, size {size}");
+        let (counterexample, original) = match shrink::<R, C>(&runtime, &generator, seed, size)? {
+            Some(shrunk) => (shrunk, Some(original)),
+            None => (original, None),
+        };
+        println!("{counterexample}");
+        return Ok(StressReport {
+            iterations,
+            counterexample: Some(counterexample),
+            original,
+        });
+    }
+    Ok(StressReport {
+        iterations,
+        counterexample: None,
+        original: None,
+    })
+}
+
+/// `size`より小さい大きさを小さい順に試し、最初に見つかった反例を返す
+/// 各大きさでは反例が見つかった`seed`を最初に試し、その後に他のシードを試す
+fn shrink<R: Solver, C: Solver>(
+    runtime: &Runtime,
+    generator: &impl Generator,
+    seed: u64,
+    size: usize,
+) -> anyhow::Result<Option<Counterexample>> {
+    let seeds = || std::iter::once(seed).chain((0..SHRINK_SEEDS).filter(move |&s| s != seed));
+    let mut candidate = 1;
+    while candidate < size {
+        for seed in seeds() {
+            if let Trial::Failed(counterexample) =
+                run::<R, C>(runtime, seed, candidate, generator, None)?
+            {
+                return Ok(Some(counterexample));
+            }
+        }
+        candidate = if candidate < SHRINK_LINEAR_SIZES {
+            candidate + 1
+        } else {
+            candidate + candidate / 4
+        };
+    }
+    Ok(None)
+}
+
+/// 1つの入力で比較した結果
+enum Trial {
+    Passed,
+    Failed(Counterexample),
+    /// 比較を終える前に`Budget::Time`を使い切った
+    OutOfBudget,
+}
+
+/// 1つの入力で想定解と解答を比較する
+fn run<R: Solver, C: Solver>(
+    runtime: &Runtime,
+    seed: u64,
+    size: usize,
+    generator: &impl Generator,
+    deadline: Option<Instant>,
+) -> anyhow::Result<Trial> {
+    let input = generator.generate(seed, size);
+    let expect = match solve::<R>(runtime, &input, deadline)? {
+        Solved::Output(expect) => expect,
+        Solved::Failed(reason, _) => {
+            return Err(anyhow!(
+                "reference solver {reason} for seed {seed}, size {size}\ninput:\n{input}"
+            ))
+        }
+        Solved::OutOfBudget => return Ok(Trial::OutOfBudget),
+    };
+    let (actual, diagnostic) = match solve::<C>(runtime, &input, deadline)? {
+        Solved::Output(actual) => {
+            let assertion =
+                judge::solver_assertion::<C>(Cow::Borrowed(&input), Cow::Borrowed(&expect));
+            match assertion.assert(&actual)? {
+                Ok(()) => return Ok(Trial::Passed),
+                Err(diagnostic) => (actual, diagnostic),
+            }
+        }
+        Solved::Failed(reason, actual) => (actual, Diagnostic::Message(reason)),
+        Solved::OutOfBudget => return Ok(Trial::OutOfBudget),
+    };
+    Ok(Trial::Failed(Counterexample {
+        seed,
+        size,
+        input,
+        expect,
+        actual,
+        diagnostic,
+    }))
+}
+
+/// 解答を1回実行した結果
+enum Solved {
+    Output(String),
+    /// 失敗の理由とそれまでの出力
+    Failed(String, String),
+    /// 実行時間制限より先に`Budget::Time`を使い切った
+    OutOfBudget,
+}
+
+/// 打ち切った解答の出力も読めるよう、スレッド間で共有する書き込み先
+#[derive(Clone, Default)]
+struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn solve_func<S: Solver>(read: &mut dyn Read, write: &mut dyn Write) {
+    S::solve(read, write)
+}
+
+/// 解答を別スレッドで実行し、実行時間制限か`deadline`の早い方で打ち切る
+fn solve<S: Solver>(
+    runtime: &Runtime,
+    input: &str,
+    deadline: Option<Instant>,
+) -> anyhow::Result<Solved> {
+    let time_limit = Duration::from_millis(VerifyConfig::get().time_limit_ms(S::TIME_LIMIT_MILLIS));
+    let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    let limit = remaining.map_or(time_limit, |remaining| remaining.min(time_limit));
+    let output = SharedOutput::default();
+    let termination = runtime.block_on(judge::run_solver(
+        solve_func::<S>,
+        input.as_bytes(),
+        output.clone(),
+        S::STACK_SIZE,
+        S::OUTPUT_LIMIT_BYTES,
+        limit,
+    ))?;
+    let actual = String::from_utf8_lossy(&output.0.lock().unwrap()).into_owned();
+    Ok(match termination {
+        Termination::Exited(_) => Solved::Output(actual),
+        Termination::Timeout if limit < time_limit => Solved::OutOfBudget,
+        Termination::Timeout => Solved::Failed("exceeded time limit".to_string(), actual),
+        Termination::Panicked(report) => Solved::Failed(report.to_string(), actual),
+        Termination::Aborted(reason) => Solved::Failed(reason, actual),
+        Termination::MemoryLimitExceeded => {
+            Solved::Failed("exceeded memory limit".to_string(), actual)
+        }
+        Termination::OutputLimitExceeded => {
+            Solved::Failed("exceeded output limit".to_string(), actual)
+        }
+    })
+}
+
+#[test]
+fn stress_test() {
+    fn read_n(mut read: impl Read) -> u64 {
+        let mut buf = String::new();
+        read.read_to_string(&mut buf).unwrap();
+        buf.trim().parse().unwrap()
+    }
+    /// 1からnまでの和
+    struct Naive;
+    impl Solver for Naive {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(read: impl Read, mut write: impl Write) {
+            writeln!(write, "{}", (1..=read_n(read)).sum::<u64>()).ok();
+        }
+    }
+    struct Formula;
+    impl Solver for Formula {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(read: impl Read, mut write: impl Write) {
+            let n = read_n(read);
+            writeln!(write, "{}", n * (n + 1) / 2).ok();
+        }
+    }
+    /// nが7の倍数の場合だけ誤り、14ではpanicする
+    struct Wrong;
+    impl Solver for Wrong {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(read: impl Read, mut write: impl Write) {
+            let n = read_n(read);
+            assert!(n != 14, "n is 14");
+            writeln!(write, "{}", n * (n + 1) / 2 + u64::from(n % 7 == 0)).ok();
+        }
+    }
+
+    let generator = |seed: u64, _| format!("{}\n", seed * 3 % 20 + 1);
+    let report = stress::<Naive, Formula>(generator, 1, Budget::Iterations(30)).unwrap();
+    assert_eq!(report.iterations, 30);
+    assert!(report.counterexample.is_none());
+
+    let report = stress::<Naive, Wrong>(generator, 1, Budget::Iterations(30)).unwrap();
+    let counterexample = report.counterexample.unwrap();
+    assert_eq!((report.iterations, counterexample.seed), (3, 2));
+    assert_eq!(counterexample.input, "7\n");
+    assert_eq!(counterexample.expect, "28\n");
+    assert_eq!(counterexample.actual, "29\n");
+    assert!(report.original.is_none());
+
+    // シード9で初めて誤り、その時の大きさ10から1まで縮小できる
+    let generator = |seed: u64, size: usize| match seed % 10 {
+        9 => format!("{}\n", 7 * size),
+        _ => "1\n".to_string(),
+    };
+    let report = stress::<Naive, Wrong>(generator, 100, Budget::Iterations(100)).unwrap();
+    let counterexample = report.counterexample.unwrap();
+    assert_eq!((counterexample.seed, counterexample.size), (9, 1));
+    assert_eq!(counterexample.input, "7\n");
+    let original = report.original.unwrap();
+    assert_eq!((original.size, original.input.as_str()), (10, "70\n"));
+
+    // 縮小で試す0..SHRINK_SEEDS以外のシード40でのみ誤るため、見つかったシードのまま縮小する
+    let generator = |seed: u64, size: usize| match seed {
+        40 => format!("{}\n", 7 * size),
+        _ => "1\n".to_string(),
+    };
+    let report = stress::<Naive, Wrong>(generator, 100, Budget::Iterations(100)).unwrap();
+    let counterexample = report.counterexample.unwrap();
+    assert_eq!((counterexample.seed, counterexample.size), (40, 1));
+    assert_eq!(report.original.unwrap().size, 41);
+
+    let generator = |_, _| "14\n".to_string();
+    let report =
+        stress::<Naive, Wrong>(generator, 1, Budget::Time(Duration::from_secs(1))).unwrap();
+    let counterexample = report.counterexample.unwrap();
+    assert!(counterexample.diagnostic.to_string().ends_with("n is 14"));
+    assert!(stress::<Wrong, Naive>(generator, 1, Budget::Iterations(1)).is_err());
+}
+
+#[test]
+fn stress_timeout_test() {
+    struct Echo;
+    impl Solver for Echo {
+        const PROBLEM_ID: &'static str = "";
+        fn solve(mut read: impl Read, mut write: impl Write) {
+            io::copy(&mut read, &mut write).unwrap();
+        }
+    }
+    /// 入力が0なら止まらない
+    struct Hangs;
+    impl Solver for Hangs {
+        const PROBLEM_ID: &'static str = "";
+        const TIME_LIMIT_MILLIS: u64 = 200;
+        fn solve(mut read: impl Read, mut write: impl Write) {
+            let mut buf = String::new();
+            read.read_to_string(&mut buf).unwrap();
+            while buf.trim() == "0" {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            write.write_all(buf.as_bytes()).unwrap();
+        }
+    }
+    let generator = |seed: u64, _| format!("{}\n", u64::from(seed != 3));
+    let report = stress::<Echo, Hangs>(generator, 1, Budget::Iterations(10)).unwrap();
+    let counterexample = report.counterexample.unwrap();
+    assert_eq!(counterexample.seed, 3);
+    assert_eq!(counterexample.diagnostic.to_string(), "exceeded time limit");
+
+    // 実行時間制限より先に時間の予算を使い切れば、反例とせずに打ち切る
+    let generator = |_, _| "0\n".to_string();
+    let now = Instant::now();
+    let report = stress::<Hangs, Echo>(generator, 1, Budget::Time(Duration::from_millis(100)));
+    assert!(report.unwrap().counterexample.is_none());
+    assert!(now.elapsed() < Duration::from_secs(2));
+}